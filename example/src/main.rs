@@ -7,6 +7,8 @@ use tower_http::trace::TraceLayer;
 #[derive(Debug, Error, IntoResponse)]
 // set the default response for StatusCode::INTERNAL_SERVER_ERROR
 #[internal_text = "overridden"]
+// added to the json response
+#[serde_field("service", "example")]
 pub enum AppError {
     #[error("This shouldn't show in the response, but will in tracing")]
     Internal,
@@ -29,6 +31,10 @@ async fn fail() -> impl IntoResponse {
     AppError::Internal
 }
 
+async fn unmasked_fail() -> impl IntoResponse {
+    AppError::Internal.into_response_masked(false)
+}
+
 async fn client_fail() -> impl IntoResponse {
     AppError::ClientError
 }
@@ -59,6 +65,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .route("/json", get(as_json))
         .route("/multiple_fields", get(multiple_fields))
         .route("/unauthorized", get(unauthorized))
+        .route("/unmasked", get(unmasked_fail))
         .layer(TraceLayer::new_for_http());
 
     tracing::info!("Listening on http://127.0.0.1:3000/");
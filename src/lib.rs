@@ -1,6 +1,7 @@
 //! Helpful `IntoResponse` derive macro for the thiserror crate
 //!
-//! To derive `IntoResponse`, the error type must implement `Debug` and `thiserror::Error`
+//! To derive `IntoResponse`, the error type must implement `Debug` and `Display`. thiserror's `#[error(...)]`
+//! is the easy way to get `Display`, but a hand-written impl works just as well
 //!
 //! Bringing your own implementation of `Debug` allows for more flexibilty
 //!
@@ -9,13 +10,155 @@
 //!
 //! You can change the default text response of a 500 status code using the `#[internal_text = "..."]` attribute
 //!
-//! Default behavior can be overridden on certain fields using the `#[status(...)]` attribute 
+//! When the crate's tracing feature is enabled, `#[internal_text_traced = "..."]` is used in its place,
+//! e.g. to point at the logs with "Something went wrong (see logs)"
 //!
-//! When overridden, the server will respond with the custom status and plain text according to your `Debug` implementation
+//! When the text is only known at response time (e.g. to include a request id), use the
+//! `#[internal_text_fn(path::to::fn)]` attribute. The function must have the signature `fn() -> String` and is
+//! preferred over `internal_text`, with a warning if both are set. It can't be combined with `internal_html`
+//!
+//! For a friendly 500 page, use the `#[internal_html = "..."]` attribute in place of `internal_text`.
+//! The masked body is then sent as `text/html`
+//!
+//! If a 500 shouldn't have a body at all, use the `#[internal_empty_body]` attribute instead
+//!
+//! For internal-only services, the `#[internal_display]` attribute on the enum shows the `Display` text of every
+//! 500 status instead, in plain text, Json and bincode alike. **This sends whatever the error carries to the
+//! client**, e.g. database errors, file paths or secrets, so never use it on a service reachable from outside.
+//! It can't be combined with `internal_html`, which would render that text as markup
+//!
+//! Default behavior can be overridden on certain fields using the `#[status(...)]` attribute. On the enum,
+//! it sets the status of every variant without its own
+//!
+//! To keep the mapping in one place, use the `#[status_fn(path::to::fn)]` attribute on the enum.
+//! The function must have the signature `fn(&ErrorType) -> StatusCode` and is called for every variant
+//! without a `#[status(...)]`
+//!
+//! When overridden, the server will respond with the custom status and plain text according to your `Display` implementation.
+//! When using thiserror, overridden variants must have an `#[error(...)]` to use as that text
+//!
+//! A single variant's text can be set with the `#[text = "..."]` attribute. On variants with named fields,
+//! `{field}` is filled in from the field of the same name
+//!
+//! For a minimal API, the `#[reason_phrase_body]` attribute on the enum responds with the canonical reason
+//! phrase of the status ("Bad Request", "Not Found") in place of the `Display` text. `#[text = "..."]` still wins,
+//! and a 500 status still responds with `internal_text`
+//!
+//! To respond with just the variant's name (e.g. "ClientError"), use the `#[name_as_body]` attribute on the
+//! variant, or on the enum for every variant. A variant's `#[text = "..."]` still wins
+//!
+//! To keep clients and CDNs from caching errors, use the `#[no_cache]` attribute on the enum to send
+//! `Cache-Control: no-store`. A variant's `#[cache_control("...")]` is sent instead where it's set
+//!
+//! For binary payloads like a protobuf message, use the `#[body_bytes(path::to::fn)]` attribute on the variant.
+//! The function must have the signature `fn(&ErrorType) -> Vec<u8>` and its bytes are sent as
+//! `application/octet-stream`, or the variant's `#[content_type("...")]`. A 500 status still hides them
+//! behind `internal_text`
+//!
+//! Headers like `WWW-Authenticate` can be added to a variant with the repeatable `#[header("name", "value")]`
+//! attribute. They're sent with every body the crate builds, so use `#[response(json)]` rather than wrapping the
+//! error in `Json(...)` to keep them on a Json response
+//!
+//! To help clients match an error with the logs, use the `#[request_id_fn(path::to::fn)]` attribute on the enum.
+//! The function must have the signature `fn() -> Option<String>`, and its id is sent as `X-Request-Id`
+//!
+//! With the `#[doc_as_message]` attribute on the enum, variants without an `#[error(...)]` use the
+//! first line of their doc comment as the text instead, or `internal_text` if they have neither
+//!
+//! Errors can be nested with the `#[delegate]` attribute on a variant with a single field whose type also
+//! derives `IntoResponse`. The status, text, response and serialization of that variant all come from the inner error,
+//! so other attributes of this crate on the variant are a compile error
+//!
+//! Variants mapped to a status that can't have a body (1xx, 204 and 304) always respond with an empty one
+//!
+//! To look the text up elsewhere (e.g. a translation table), use the `#[message_fn(path::to::fn)]` attribute.
+//! The function must have the signature `fn(&ErrorType) -> String` and is used in place of `to_string()`
 //!
 //! If you'd like a Json response, enable the crate's serde feature, and wrap the enum in `Json(...)`
 //!
-//! To display the internal error with tracing, enable the crate's tracing feature
+//! To always respond with Json instead, use the `#[response(json)]` attribute on the enum or on single variants.
+//! `#[response(text)]` on a variant keeps it plain text, the variant's attribute wins over the enum's.
+//! Json bodies hide the error text of a 500 status like text bodies, following `into_response_masked`. Only the
+//! plain `Serialize` impl, e.g. through `Json(...)` or `to_json_value`, always hides it
+//!
+//! Without the serde feature, `#[response(raw_json)]` writes the same `{"status":...,"error":"..."}`
+//! envelope by hand, escaping the text so no serializer is needed
+//!
+//! To serialize the field of a single-field variant as `error` instead of its text, use the
+//! `#[serde_inline_field]` attribute on the variant. The field is hidden like the text for a 500 status
+//!
+//! Constant fields can be added to every serialized error with the repeatable `#[serde_field("key", "value")]`
+//! attribute. They follow `status` and `error` in declaration order
+//!
+//! Variants can be grouped with the `#[category = "..."]` attribute, which is serialized as `kind`.
+//! Once any variant has a category, the others serialize `"kind": "internal"`, or leave `kind` out
+//! with the `#[skip_uncategorized]` attribute on the enum
+//!
+//! Serialized keys are `snake_case`. For other clients, use the `#[json_case = "..."]` attribute on the enum
+//! with `camelCase`, `PascalCase` or `kebab-case`, which applies to every key including `serde_field`'s
+//!
+//! With the `#[serde_cause]` attribute on the enum, the `Display` of a variant's `#[source]` or `#[from]` field
+//! is serialized as `cause`, which is null for variants without one and for a 500 status.
+//! `#[serde_cause(skip_none)]` leaves `cause` out instead of sending null.
+//! Binary formats like bincode have no keys to leave out, so the skipped `kind` and `cause` are sent as `None`
+//! there and every variant keeps the same layout
+//!
+//! For a machine-readable identifier that survives rewording, use the `#[code("...")]` attribute, which is
+//! serialized as `error_code`. Once any variant has a code, the others use their name in
+//! SCREAMING_SNAKE_CASE (`ClientError` becomes `CLIENT_ERROR`)
+//!
+//! To merge the error into a larger document, enable the crate's serde_json feature and call the generated
+//! `to_json_value(&self)`
+//!
+//! For service-to-service calls, enable the crate's bincode feature and call the generated
+//! `into_bincode_response(self)` to send the same envelope encoded with bincode 2 (`application/octet-stream`)
+//!
+//! To display the internal error with tracing, enable the crate's tracing feature.
+//! The event carries the `status`, `variant` name and `error` message as structured fields.
+//! With thiserror, the message is followed by the `source()` chain, e.g. of a boxed error behind `#[from]`.
+//! Use the `#[trace_debug]` attribute on the enum to log the `Debug` output instead of `Display`.
+//! Variants carrying personal data can be marked `#[sensitive]`, which logs only their name and status
+//!
+//! When a client asks for `text/plain`, call the generated `into_response_text(self)` to respond with the
+//! plain text body regardless of `#[response(...)]` and `#[internal_html]`
+//!
+//! As an escape hatch, the `#[response_fn(path::to::fn)]` attribute on the enum hands `into_response` to a
+//! function with the signature `fn(ErrorType) -> Response`. Everything else is still generated, so the function
+//! can start from `into_response_masked(true)` (calling `into_response` would recurse) or build the response
+//! from `status_code()` and `response_text(...)`. The derive's tracing, headers and representation only apply
+//! to the response if the function keeps them
+//!
+//! To decide on masking at runtime, call the generated `into_response_masked(self, mask: bool)`.
+//! `into_response` is the same as `into_response_masked(true)`
+//!
+//! The generated `status_code(&self)` and `response_text(&self, mask: bool)` accessors expose the parts of
+//! the response, and `to_response(&self)` builds the response without consuming the error
+//!
+//! With the `#[status_from]` attribute on the enum, `From<&ErrorType> for StatusCode` is implemented too,
+//! so `let code: StatusCode = (&err).into()` works
+//!
+//! Going the other way, the `#[from_status]` attribute on the enum generates `from_status(code: StatusCode)`,
+//! returning the first unit variant declared with that `#[status(...)]`, or `None` if there's none.
+//! Variants with fields can't be built from a status alone, so they're skipped
+//!
+//! On large fieldless enums, the `#[status_table]` attribute generates a `STATUS_CODES` table of
+//! `(discriminant, status)` pairs, which `status_code(&self)` looks up instead of matching on every variant.
+//! The lookup is a binary search when the variants are declared in order of their discriminant, and a scan
+//! of the table otherwise. The enum must be `Copy` to read its discriminant, and every discriminant must
+//! fit in a `u16`. Enums with fields keep the match
+//!
+//! For OpenAPI docs, the `#[openapi]` attribute on the enum generates `response_examples()`, listing the status
+//! and example body of every variant. The body is the variant's `#[text = "..."]`, the format string of its
+//! `#[error(...)]` or the first line of its doc comment, and the masked body for a 500 status. Variants without a
+//! `#[status(...)]` are left out when `#[status_fn(...)]` picks their status, and so are `#[delegate]` variants
+//!
+//! Only enums can derive `IntoResponse`. An empty enum is fine, e.g. for a handler that can't fail yet
+//!
+//! An attribute in the wrong place, e.g. `#[no_cache]` on a variant, is an error rather than being ignored,
+//! and so is a typo like `#[staus(...)]`, which comes with the attribute it's closest to
+//!
+//! The generated accessors are `#[must_use]`, so a status or response that's computed and then dropped is
+//! caught by the `unused_must_use` lint
 //!
 //! ## Example
 //!
@@ -45,179 +188,3442 @@
 //! }
 //! ```
 //!
-//! A working example with all the features enabled can be viewed [in the repo](https://github.com/ozpv/axum_thiserror_intoresponse_derive/blob/main/example/src/main.rs)
+//! ## Without thiserror
 //!
-//! ## Contributing
+//! ```rust
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! use axum::response::IntoResponse;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use std::fmt;
 //!
-//! All contributions are welcome. Just open a pull request in the repo.
+//! #[derive(Debug, IntoResponse)]
+//! pub enum AppError {
+//!     #[status(StatusCode::FORBIDDEN)]
+//!     Forbidden,
+//! }
 //!
-//! ## License
+//! impl fmt::Display for AppError {
+//!     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+//!         f.write_str("You can't do that")
+//!     }
+//! }
 //!
-//! This lib is licensed under the [MIT license](https://github.com/ozpv/axum_thiserror_intoresponse_derive/blob/main/LICENSE)
+//! let res = AppError::Forbidden.into_response();
+//! let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+//! assert_eq!(body, "You can't do that");
+//! # }
+//! ```
+//!
+//! ## Reason phrases
+//!
+//! ```rust
+//! use axum::http::StatusCode;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[reason_phrase_body]
+//! pub enum ApiError {
+//!     #[status(StatusCode::NOT_FOUND)]
+//!     #[error("No row in users with id 7")]
+//!     UserNotFound,
+//!     #[status(StatusCode::TOO_MANY_REQUESTS)]
+//!     #[text = "Slow down"]
+//!     #[error("Rate limit of 10/s hit")]
+//!     RateLimited,
+//!     #[error("Pool exhausted")]
+//!     Database,
+//! }
+//!
+//! assert_eq!(ApiError::UserNotFound.response_text(true), "Not Found");
+//! assert_eq!(ApiError::RateLimited.response_text(true), "Slow down");
+//! assert_eq!(ApiError::Database.response_text(true), "Something went wrong");
+//! ```
+//!
+//! ## Names as the body
+//!
+//! ```rust
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! use axum::response::IntoResponse;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use std::fmt;
+//!
+//! #[derive(Debug, IntoResponse)]
+//! #[name_as_body]
+//! pub enum ApiError {
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     ClientError,
+//!     #[status(StatusCode::CONFLICT)]
+//!     #[text = "Already exists"]
+//!     Conflict,
+//! }
+//!
+//! // only used for tracing now
+//! impl fmt::Display for ApiError {
+//!     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+//!         fmt::Debug::fmt(self, f)
+//!     }
+//! }
+//!
+//! let res = ApiError::ClientError.into_response();
+//! let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+//! assert_eq!(body, "ClientError");
+//! assert_eq!(ApiError::Conflict.response_text(true), "Already exists");
+//! # }
+//! ```
+//!
+//! ## Status precedence
+//!
+//! The status of a variant is resolved from the first of these that applies
+//!
+//! 1. the variant's `#[status(...)]`
+//! 2. the enum's `#[status(...)]`
+//! 3. the enum's `#[status_fn(path::to::fn)]`, calling `fn(&ErrorType) -> StatusCode`
+//! 4. `StatusCode::INTERNAL_SERVER_ERROR`
+//!
+//! An enum can't have both a `#[status(...)]` and a `#[status_fn(...)]`
+//!
+//! ```rust
+//! use axum::http::StatusCode;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[status(StatusCode::BAD_REQUEST)]
+//! pub enum ValidationError {
+//!     #[error("Name is too long")]
+//!     NameTooLong,
+//!     #[error("Email is invalid")]
+//!     InvalidEmail,
+//!     #[status(StatusCode::CONFLICT)]
+//!     #[error("Email is taken")]
+//!     EmailTaken,
+//! }
+//!
+//! assert_eq!(ValidationError::NameTooLong.status_code(), StatusCode::BAD_REQUEST);
+//! assert_eq!(ValidationError::InvalidEmail.response_text(true), "Email is invalid");
+//! assert_eq!(ValidationError::EmailTaken.status_code(), StatusCode::CONFLICT);
+//! ```
+//!
+//! ```rust
+//! use axum::http::StatusCode;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[status_fn(status_of)]
+//! pub enum AppError {
+//!     #[error("Not found")]
+//!     NotFound,
+//!     #[error("Gone")]
+//!     Gone,
+//!     // the variant's own status wins
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     #[error("Bad request")]
+//!     BadRequest,
+//! }
+//!
+//! // shared by several error types, or driven by data
+//! fn status_of(err: &AppError) -> StatusCode {
+//!     match err {
+//!         AppError::NotFound => StatusCode::NOT_FOUND,
+//!         _ => StatusCode::GONE,
+//!     }
+//! }
+//!
+//! assert_eq!(AppError::NotFound.status_code(), StatusCode::NOT_FOUND);
+//! assert_eq!(AppError::BadRequest.status_code(), StatusCode::BAD_REQUEST);
+//! ```
+//!
+//! The status is any expression evaluating to a `StatusCode`, resolved just like in your own code, with
+//! `StatusCode` in scope even when it isn't imported
+//!
+//! ```rust
+//! use axum::http::StatusCode as SC;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! pub enum AppError {
+//!     #[status(SC::BAD_REQUEST)]
+//!     #[error("Bad request")]
+//!     Aliased,
+//!     #[status(StatusCode::from_u16(418).unwrap())]
+//!     #[error("I'm a teapot")]
+//!     Teapot,
+//! }
+//!
+//! assert_eq!(AppError::Aliased.status_code(), SC::BAD_REQUEST);
+//! assert_eq!(AppError::Teapot.status_code(), SC::IM_A_TEAPOT);
+//! ```
+//!
+//! A variant may only carry one `#[status(...)]`, anything more is a compile error
+//!
+//! Two variants with the same status and the same body can't be told apart by clients, which is usually a
+//! copy-paste mistake, so they're rejected too
+//!
+//! Only bodies that are the same on every response are compared, i.e. a `#[text = "..."]` or `#[error("...")]`
+//! without placeholders. Variants that fill in their fields or are `#[error(transparent)]` can differ at
+//! runtime, so they're fine
+//!
+//! ```rust
+//! # use axum_thiserror_tracing::IntoResponse;
+//! # use thiserror::Error;
+//! #[derive(Debug, Error, IntoResponse)]
+//! pub enum AppError {
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     #[error(transparent)]
+//!     Int(#[from] std::num::ParseIntError),
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     #[error(transparent)]
+//!     Float(#[from] std::num::ParseFloatError),
+//!     #[status(StatusCode::NOT_FOUND)]
+//!     #[error("{0}")]
+//!     User(String),
+//!     #[status(StatusCode::NOT_FOUND)]
+//!     #[error("{0}")]
+//!     Team(String),
+//! }
+//! ```
+//!
+//! Neither are variants with a different `#[code(...)]`, `#[header(...)]` or `#[response(...)]`, which clients
+//! can tell apart, or variants sending `#[body_bytes(...)]`
+//!
+//! ```rust
+//! # use axum_thiserror_tracing::IntoResponse;
+//! # use thiserror::Error;
+//! #[derive(Debug, Error, IntoResponse)]
+//! pub enum SignupError {
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     #[code("INVALID_NAME")]
+//!     #[error("Invalid input")]
+//!     InvalidName,
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     #[code("INVALID_EMAIL")]
+//!     #[error("Invalid input")]
+//!     InvalidEmail,
+//! }
+//! ```
+//!
+//! If that's intended, e.g. to keep the variants apart in your own code, add `#[allow_duplicate_status]`
+//! to the enum
+//!
+//! ```rust
+//! # use axum_thiserror_tracing::IntoResponse;
+//! # use thiserror::Error;
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[allow_duplicate_status]
+//! pub enum AppError {
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     #[error("Invalid input")]
+//!     InvalidName,
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     #[error("Invalid input")]
+//!     InvalidEmail,
+//! }
+//! ```
+//!
+//! When every variant has a `#[status(...)]`, the generated match is exhaustive without a catch-all,
+//! so crates denying warnings don't trip over `unreachable_patterns`
+//!
+//! ```rust
+//! #![deny(warnings)]
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! pub enum AppError {
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     #[text = "Bad request"]
+//!     #[error("Bad request")]
+//!     BadRequest,
+//!     #[status(StatusCode::NOT_FOUND)]
+//!     #[text = "Not found"]
+//!     #[error("Not found")]
+//!     NotFound,
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! ## Borrowed data
+//!
+//! Lifetimes and type parameters are carried into the generated impls
+//!
+//! ```rust
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! use axum::response::IntoResponse;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! pub enum AppError<'a> {
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     #[error("{0}")]
+//!     Bad(&'a str),
+//! }
+//!
+//! let input = String::from("missing field `name`");
+//! let res = AppError::Bad(&input).into_response();
+//! let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+//! assert_eq!(body, "missing field `name`");
+//! # }
+//! ```
+//!
+//! A type parameter only needs the bounds thiserror needs, the impls are only there when the error
+//! can be displayed
+//!
+//! ```rust
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! use axum::response::IntoResponse;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use std::fmt::Debug;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! pub enum AppError<T: Debug> {
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     #[error("{0}")]
+//!     Bad(T),
+//! }
+//!
+//! let res = AppError::Bad(42).into_response();
+//! assert_eq!(res.status(), axum::http::StatusCode::BAD_REQUEST);
+//! let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+//! assert_eq!(body, "42");
+//! # }
+//! ```
+//!
+//! ## Borrowing
+//!
+//! ```rust
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[status_from]
+//! pub enum AppError {
+//!     #[status(StatusCode::CONFLICT)]
+//!     #[error("Username taken")]
+//!     Taken,
+//! }
+//!
+//! let err = AppError::Taken;
+//! assert_eq!(err.to_response().status(), axum::http::StatusCode::CONFLICT);
+//!
+//! let code: axum::http::StatusCode = (&err).into();
+//! assert_eq!(code, axum::http::StatusCode::CONFLICT);
+//! // the error is still around to build another response
+//! assert_eq!(err.to_response().status(), err.status_code());
+//! ```
+//!
+//! ## Statuses from upstream
+//!
+//! ```rust
+//! use axum::http::StatusCode;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse, PartialEq)]
+//! #[from_status]
+//! pub enum UpstreamError {
+//!     #[status(StatusCode::NOT_FOUND)]
+//!     #[error("Not found")]
+//!     NotFound,
+//!     #[status(StatusCode::NOT_FOUND)]
+//!     #[error("Gone")]
+//!     Gone,
+//!     // can't be built from a status alone
+//!     #[status(StatusCode::TOO_MANY_REQUESTS)]
+//!     #[error("Retry in {0}s")]
+//!     RateLimited(u64),
+//! }
+//!
+//! // the first variant declared with the status wins
+//! assert_eq!(UpstreamError::from_status(StatusCode::NOT_FOUND), Some(UpstreamError::NotFound));
+//! assert_eq!(UpstreamError::from_status(StatusCode::TOO_MANY_REQUESTS), None);
+//! assert_eq!(UpstreamError::from_status(StatusCode::BAD_GATEWAY), None);
+//! ```
+//!
+//! ## Text from fields
+//!
+//! ```rust
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! use axum::response::IntoResponse;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! pub enum AppError {
+//!     #[status(StatusCode::NOT_FOUND)]
+//!     #[text = "User {id} not found"]
+//!     #[error("lookup of user {id} in {table} failed")]
+//!     UserNotFound { id: u64, table: &'static str },
+//! }
+//!
+//! let res = AppError::UserNotFound { id: 7, table: "users" }.into_response();
+//! let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+//! assert_eq!(body, "User 7 not found");
+//! # }
+//! ```
+//!
+//! Braces are escaped as `{{` and `}}`, like in `format!`, whether or not the text has fields in it
+//!
+//! ```rust
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! pub enum AppError {
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     #[text = "Expected {{\"name\": ...}}"]
+//!     #[error("missing name")]
+//!     MissingName,
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     #[text = "Expected {{\"{field}\": ...}}"]
+//!     #[error("missing {field}")]
+//!     MissingField { field: &'static str },
+//! }
+//!
+//! assert_eq!(AppError::MissingName.response_text(true), "Expected {\"name\": ...}");
+//! assert_eq!(
+//!     AppError::MissingField { field: "email" }.response_text(true),
+//!     "Expected {\"email\": ...}"
+//! );
+//! ```
+//!
+//! Referring to a field that doesn't exist is a compile error
+//!
+//! ## Caching
+//!
+//! ```rust
+//! use axum::response::IntoResponse;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[no_cache]
+//! pub enum AppError {
+//!     #[error("Database connection refused")]
+//!     Database,
+//!     // safe to cache for a bit
+//!     #[status(StatusCode::NOT_FOUND)]
+//!     #[cache_control("max-age=60")]
+//!     #[error("Not found")]
+//!     NotFound,
+//! }
+//!
+//! let res = AppError::Database.into_response();
+//! assert_eq!(res.headers()["cache-control"], "no-store");
+//! let res = AppError::NotFound.into_response();
+//! assert_eq!(res.headers()["cache-control"], "max-age=60");
+//! ```
+//!
+//! ## Binary bodies
+//!
+//! ```rust
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! use axum::response::IntoResponse;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! pub enum RpcError {
+//!     #[status(StatusCode::NOT_FOUND)]
+//!     #[body_bytes(encode_status)]
+//!     #[content_type("application/x-protobuf")]
+//!     #[error("Not found")]
+//!     NotFound,
+//! }
+//!
+//! // e.g. an encoded google.rpc.Status
+//! fn encode_status(err: &RpcError) -> Vec<u8> {
+//!     let mut bytes = vec![0x08, 0x05, 0x12];
+//!     let message = err.to_string();
+//!     bytes.push(message.len() as u8);
+//!     bytes.extend(message.into_bytes());
+//!     bytes
+//! }
+//!
+//! let res = RpcError::NotFound.into_response();
+//! assert_eq!(res.status(), 404);
+//! assert_eq!(res.headers()["content-type"], "application/x-protobuf");
+//! let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+//! assert_eq!(&body[..], b"\x08\x05\x12\x09Not found");
+//! # }
+//! ```
+//!
+//! ## Headers
+//!
+//! ```rust
+//! use axum::response::IntoResponse;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! pub enum AuthError {
+//!     #[status(StatusCode::UNAUTHORIZED)]
+//!     #[header("WWW-Authenticate", "Bearer")]
+//!     #[error("Missing token")]
+//!     MissingToken,
+//! }
+//!
+//! let res = AuthError::MissingToken.into_response();
+//! assert_eq!(res.headers()["www-authenticate"], "Bearer");
+//!
+//! # #[cfg(feature = "serde")]
+//! # {
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[response(json)]
+//! pub enum ApiAuthError {
+//!     #[status(StatusCode::UNAUTHORIZED)]
+//!     #[header("WWW-Authenticate", "Bearer")]
+//!     #[error("Missing token")]
+//!     MissingToken,
+//! }
+//!
+//! let res = ApiAuthError::MissingToken.into_response();
+//! assert_eq!(res.headers()["content-type"], "application/json");
+//! assert_eq!(res.headers()["www-authenticate"], "Bearer");
+//! # }
+//! ```
+//!
+//! ## Request ids
+//!
+//! ```rust
+//! use axum::response::IntoResponse;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use std::cell::RefCell;
+//! use thiserror::Error;
+//!
+//! thread_local! {
+//!     static REQUEST_ID: RefCell<Option<String>> = const { RefCell::new(None) };
+//! }
+//!
+//! fn request_id() -> Option<String> {
+//!     REQUEST_ID.with_borrow(Clone::clone)
+//! }
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[request_id_fn(request_id)]
+//! pub enum AppError {
+//!     #[error("Connection refused")]
+//!     Database,
+//! }
+//!
+//! // no id, no header
+//! let res = AppError::Database.into_response();
+//! assert!(res.headers().get("x-request-id").is_none());
+//!
+//! REQUEST_ID.set(Some(String::from("3f2a")));
+//! let res = AppError::Database.into_response();
+//! assert_eq!(res.headers()["x-request-id"], "3f2a");
+//! ```
+//!
+//! ## Doc comments as text
+//!
+//! ```rust
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! use axum::response::IntoResponse;
+//! use axum_thiserror_tracing::IntoResponse;
+//!
+//! #[derive(Debug, IntoResponse)]
+//! #[doc_as_message]
+//! pub enum AppError {
+//!     /// The requested page doesn't exist
+//!     ///
+//!     /// Only the first line is sent
+//!     #[status(StatusCode::NOT_FOUND)]
+//!     NotFound,
+//! }
+//!
+//! impl std::fmt::Display for AppError {
+//!     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//!         write!(f, "{self:?}")
+//!     }
+//! }
+//!
+//! let res = AppError::NotFound.into_response();
+//! let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+//! assert_eq!(body, "The requested page doesn't exist");
+//! # }
+//! ```
+//!
+//! ## Nested errors
+//!
+//! ```rust
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! use axum::response::IntoResponse;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[internal_text = "Database unavailable"]
+//! pub enum DbError {
+//!     #[status(StatusCode::NOT_FOUND)]
+//!     #[error("Row not found")]
+//!     NotFound,
+//!     #[error("Connection refused")]
+//!     Connection,
+//! }
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! pub enum ApiError {
+//!     #[delegate]
+//!     #[error(transparent)]
+//!     Db(#[from] DbError),
+//! }
+//!
+//! let res = ApiError::Db(DbError::NotFound).into_response();
+//! assert_eq!(res.status(), axum::http::StatusCode::NOT_FOUND);
+//!
+//! // the inner error's own internal_text is used
+//! let res = ApiError::from(DbError::Connection).into_response();
+//! let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+//! assert_eq!(body, "Database unavailable");
+//! # }
+//! ```
+//!
+//! ## No content
+//!
+//! ```rust
+//! use axum::{body::HttpBody, response::IntoResponse};
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! pub enum Signal {
+//!     #[status(StatusCode::NO_CONTENT)]
+//!     #[error("Nothing to do")]
+//!     NothingToDo,
+//!     #[status(StatusCode::NOT_MODIFIED)]
+//!     #[error("Unchanged")]
+//!     Unchanged,
+//! }
+//!
+//! for signal in [Signal::NothingToDo, Signal::Unchanged] {
+//!     let res = signal.into_response();
+//!     assert_eq!(res.body().size_hint().exact(), Some(0));
+//!     assert!(res.headers().get("content-type").is_none());
+//! }
+//! ```
+//!
+//! ## Message lookup
+//!
+//! ```rust
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! use axum::response::IntoResponse;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[message_fn(translate)]
+//! pub enum AppError {
+//!     #[status(StatusCode::NOT_FOUND)]
+//!     #[error("Page not found")]
+//!     NotFound,
+//! }
+//!
+//! fn translate(err: &AppError) -> String {
+//!     match err {
+//!         AppError::NotFound => String::from("Page introuvable"),
+//!     }
+//! }
+//!
+//! let res = AppError::NotFound.into_response();
+//! let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+//! assert_eq!(body, "Page introuvable");
+//! # }
+//! ```
+//!
+//! ## Html internal body
+//!
+//! ```rust
+//! use axum::response::IntoResponse;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[internal_html = "<h1>Oops</h1><p>Please try again later</p>"]
+//! pub enum AppError {
+//!     #[error("Database connection refused")]
+//!     Database,
+//! }
+//!
+//! let res = AppError::Database.into_response();
+//! assert_eq!(res.headers()["content-type"], "text/html; charset=utf-8");
+//! ```
+//!
+//! Setting both is a compile error
+//!
+//! ## Generic code
+//!
+//! A proc-macro crate can only export macros, so there is no trait to implement here. To accept any
+//! derived error in generic code, declare a trait of your own and forward it to the generated `status_code`
+//!
+//! ```rust
+//! use axum::http::StatusCode;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! pub trait IntoResponseExt {
+//!     fn status_code(&self) -> StatusCode;
+//! }
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! pub enum AppError {
+//!     #[status(StatusCode::TOO_MANY_REQUESTS)]
+//!     #[error("Slow down")]
+//!     RateLimited,
+//! }
+//!
+//! impl IntoResponseExt for AppError {
+//!     fn status_code(&self) -> StatusCode {
+//!         AppError::status_code(self)
+//!     }
+//! }
+//!
+//! // e.g. labeling a metric in a middleware
+//! fn metric_label<E: IntoResponseExt>(err: &E) -> u16 {
+//!     err.status_code().as_u16()
+//! }
+//!
+//! assert_eq!(metric_label(&AppError::RateLimited), 429);
+//! ```
+//!
+//! ## Internal text at response time
+//!
+//! ```rust
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! use axum::response::IntoResponse;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use std::cell::Cell;
+//! use thiserror::Error;
+//!
+//! thread_local! {
+//!     static REQUEST_ID: Cell<u64> = const { Cell::new(0) };
+//! }
+//!
+//! fn internal_text() -> String {
+//!     format!("Something went wrong (request {})", REQUEST_ID.get())
+//! }
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[internal_text_fn(internal_text)]
+//! pub enum AppError {
+//!     #[error("Connection refused")]
+//!     Database,
+//! }
+//!
+//! REQUEST_ID.set(42);
+//! let res = AppError::Database.into_response();
+//! let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+//! assert_eq!(&body[..], b"Something went wrong (request 42)");
+//! # }
+//! ```
+//!
+//! ## Internal display
+//!
+//! ```rust
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! use axum::response::IntoResponse;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! // only reachable from inside the cluster
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[internal_display]
+//! pub enum JobError {
+//!     #[error("Queue {0} is full")]
+//!     QueueFull(&'static str),
+//! }
+//!
+//! let res = JobError::QueueFull("emails").into_response();
+//! assert_eq!(res.status(), 500);
+//! let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+//! assert_eq!(&body[..], b"Queue emails is full");
+//! # }
+//! ```
+//!
+//! It can't be combined with `#[internal_html]`, which would otherwise serve the error text as HTML
+//!
+//! ## Empty internal body
+//!
+//! ```rust
+//! use axum::{body::HttpBody, response::IntoResponse};
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! // the status is kept, but nothing about the error is sent
+//! #[internal_empty_body]
+//! pub enum AppError {
+//!     #[error("Database connection refused")]
+//!     Database,
+//! }
+//!
+//! let res = AppError::Database.into_response();
+//! assert_eq!(res.status(), axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+//! // Content-Length: 0
+//! assert_eq!(res.body().size_hint().exact(), Some(0));
+//! ```
+//!
+//! ## Json per variant
+//!
+//! ```rust
+//! # #[cfg(feature = "serde")]
+//! # {
+//! use axum::response::IntoResponse;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[response(json)]
+//! pub enum AppError {
+//!     #[status(StatusCode::UNPROCESSABLE_ENTITY)]
+//!     #[error("Invalid email")]
+//!     Validation,
+//!     // a simple 404 doesn't need the envelope
+//!     #[response(text)]
+//!     #[status(StatusCode::NOT_FOUND)]
+//!     #[error("Not found")]
+//!     NotFound,
+//! }
+//!
+//! let res = AppError::Validation.into_response();
+//! assert_eq!(res.headers()["content-type"], "application/json");
+//! let res = AppError::NotFound.into_response();
+//! assert_eq!(res.headers()["content-type"], "text/plain; charset=utf-8");
+//!
+//! // e.g. for a client sending `Accept: text/plain`
+//! let res = AppError::Validation.into_response_text();
+//! assert_eq!(res.status(), 422);
+//! assert_eq!(res.headers()["content-type"], "text/plain; charset=utf-8");
+//! # }
+//! ```
+//!
+//! ## Json without serde
+//!
+//! ```rust
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! use axum::response::IntoResponse;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[response(raw_json)]
+//! pub enum AppError {
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     #[error("Unexpected {0}")]
+//!     Unexpected(&'static str),
+//! }
+//!
+//! let res = AppError::Unexpected("\"quote\" \\ \n\u{1}").into_response();
+//! assert_eq!(res.headers()["content-type"], "application/json");
+//! let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+//! assert_eq!(body, r#"{"status":400,"error":"Unexpected \"quote\" \\ \n\u0001"}"#);
+//! # }
+//! ```
+//!
+//! ## Json value
+//!
+//! ```rust
+//! # #[cfg(feature = "serde_json")]
+//! # {
+//! use axum_thiserror_tracing::IntoResponse;
+//! use serde_json::json;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[serde_field("service", "billing")]
+//! pub enum AppError {
+//!     #[status(StatusCode::PAYMENT_REQUIRED)]
+//!     #[category = "payment"]
+//!     #[code("CARD_DECLINED")]
+//!     #[error("Card declined")]
+//!     Declined,
+//!     #[error("Ledger out of balance")]
+//!     LedgerImbalance,
+//! }
+//!
+//! let value = AppError::Declined.to_json_value();
+//! assert_eq!(
+//!     value,
+//!     json!({
+//!         "status": 402,
+//!         "error": "Card declined",
+//!         "kind": "payment",
+//!         "error_code": "CARD_DECLINED",
+//!         "service": "billing"
+//!     })
+//! );
+//!
+//! let value = AppError::LedgerImbalance.to_json_value();
+//! assert_eq!(value["kind"], "internal");
+//! assert_eq!(value["error_code"], "LEDGER_IMBALANCE");
+//! # }
+//! ```
+//!
+//! ```rust
+//! # #[cfg(feature = "serde_json")]
+//! # {
+//! use axum_thiserror_tracing::IntoResponse;
+//! use serde_json::json;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[json_case = "camelCase"]
+//! #[serde_field("service_name", "billing")]
+//! pub enum AppError {
+//!     #[status(StatusCode::PAYMENT_REQUIRED)]
+//!     #[code("CARD_DECLINED")]
+//!     #[error("Card declined")]
+//!     Declined,
+//! }
+//!
+//! assert_eq!(
+//!     AppError::Declined.to_json_value(),
+//!     json!({
+//!         "status": 402,
+//!         "error": "Card declined",
+//!         "errorCode": "CARD_DECLINED",
+//!         "serviceName": "billing"
+//!     })
+//! );
+//! # }
+//! ```
+//!
+//! ## Inline fields
+//!
+//! ```rust
+//! # #[cfg(feature = "serde_json")]
+//! # {
+//! use axum_thiserror_tracing::IntoResponse;
+//! use serde_json::json;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! pub enum AppError {
+//!     #[status(StatusCode::UNAUTHORIZED)]
+//!     #[serde_inline_field]
+//!     #[error("Error: {0}")]
+//!     AuthError(&'static str),
+//!     #[status(StatusCode::UNPROCESSABLE_ENTITY)]
+//!     #[serde_inline_field]
+//!     #[error("Invalid fields")]
+//!     Invalid { fields: Vec<&'static str> },
+//! }
+//!
+//! let value = AppError::AuthError("token expired").to_json_value();
+//! assert_eq!(value, json!({ "status": 401, "error": "token expired" }));
+//!
+//! let value = AppError::Invalid { fields: vec!["email"] }.to_json_value();
+//! assert_eq!(value["error"], json!(["email"]));
+//! # }
+//! ```
+//!
+//! Only a variant with exactly one field can be inlined, anything else is a compile error
+//!
+//! ## Causes
+//!
+//! ```rust
+//! # #[cfg(feature = "serde_json")]
+//! # {
+//! use axum_thiserror_tracing::IntoResponse;
+//! use serde_json::json;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[serde_cause]
+//! pub enum UploadError {
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     #[error("Invalid manifest")]
+//!     Manifest(#[from] serde_json::Error),
+//!     #[status(StatusCode::PAYLOAD_TOO_LARGE)]
+//!     #[error("File too large")]
+//!     TooLarge,
+//! }
+//!
+//! let err: UploadError = serde_json::from_str::<u8>("").unwrap_err().into();
+//! let value = err.to_json_value();
+//! assert_eq!(value["error"], "Invalid manifest");
+//! assert_eq!(value["cause"], "EOF while parsing a value at line 1 column 0");
+//!
+//! assert_eq!(
+//!     UploadError::TooLarge.to_json_value(),
+//!     json!({ "status": 413, "error": "File too large", "cause": null })
+//! );
+//! # }
+//! ```
+//!
+//! ## Bincode
+//!
+//! ```rust
+//! # #[cfg(feature = "bincode")]
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! pub enum AppError {
+//!     #[status(StatusCode::NOT_FOUND)]
+//!     #[error("No such user")]
+//!     NotFound,
+//! }
+//!
+//! let res = AppError::NotFound.into_bincode_response();
+//! assert_eq!(res.headers()["content-type"], "application/octet-stream");
+//! let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+//!
+//! // the envelope is the status followed by the error text
+//! let ((status, error), _): ((u16, String), _) =
+//!     bincode::serde::decode_from_slice(&body, bincode::config::standard()).unwrap();
+//! assert_eq!(status, 404);
+//! assert_eq!(error, "No such user");
+//! # }
+//! # #[cfg(not(feature = "bincode"))]
+//! # fn main() {}
+//! ```
+//!
+//! ## Status table
+//!
+//! ```rust
+//! use axum::http::StatusCode;
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Clone, Copy, Debug, Error, IntoResponse)]
+//! #[status_table]
+//! pub enum ApiError {
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     #[error("Bad request")]
+//!     BadRequest = 100,
+//!     #[status(StatusCode::NOT_FOUND)]
+//!     #[error("Not found")]
+//!     NotFound = 200,
+//!     #[error("Internal")]
+//!     Internal = 300,
+//! }
+//!
+//! assert_eq!(ApiError::STATUS_CODES, &[(100, 400), (200, 404)]);
+//! assert_eq!(ApiError::NotFound.status_code(), StatusCode::NOT_FOUND);
+//! // variants without a status aren't in the table
+//! assert_eq!(ApiError::Internal.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+//! ```
+//!
+//! ## Response examples
+//!
+//! ```rust
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[openapi]
+//! #[internal_text = "Internal error"]
+//! pub enum AppError {
+//!     #[error("Database is down")]
+//!     Database,
+//!     #[status(StatusCode::NOT_FOUND)]
+//!     #[error("No user with id {0}")]
+//!     UserNotFound(u64),
+//!     #[status(StatusCode::UNAUTHORIZED)]
+//!     #[text = "Log in first"]
+//!     #[error("Missing session cookie")]
+//!     Unauthorized,
+//! }
+//!
+//! assert_eq!(
+//!     AppError::response_examples(),
+//!     vec![
+//!         (500, "Internal error"),
+//!         (404, "No user with id {0}"),
+//!         (401, "Log in first"),
+//!     ]
+//! );
+//! ```
+//!
+//! ## Sensitive variants
+//!
+//! ```rust
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! pub enum AppError {
+//!     // logged as `variant="Login"` without the address
+//!     #[sensitive]
+//!     #[error("Failed login for {email}")]
+//!     Login { email: String },
+//!     #[error("Connection refused")]
+//!     Database,
+//! }
+//! # let _ = AppError::Login { email: String::from("user@example.com") }.to_response();
+//! ```
+//!
+//! ## Custom responses
+//!
+//! ```rust
+//! use axum::response::{IntoResponse, Response};
+//! use axum_thiserror_tracing::IntoResponse;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! #[response_fn(respond)]
+//! pub enum AppError {
+//!     #[status(StatusCode::SERVICE_UNAVAILABLE)]
+//!     #[error("Down for maintenance")]
+//!     Maintenance,
+//! }
+//!
+//! fn respond(err: AppError) -> Response {
+//!     let retry = matches!(err, AppError::Maintenance);
+//!     let mut res = err.into_response_masked(true);
+//!     if retry {
+//!         res.headers_mut().insert("retry-after", "120".parse().unwrap());
+//!     }
+//!     res
+//! }
+//!
+//! let res = AppError::Maintenance.into_response();
+//! assert_eq!(res.status(), 503);
+//! assert_eq!(res.headers()["retry-after"], "120");
+//! ```
+//!
+//! ## Masking per request
+//!
+//! The derive can't read the request, so keep the masking policy at the edge with a middleware
+//! that inserts a `MaskErrors` extension, then hand the flag to `into_response_masked` in your handlers
+//!
+//! ```rust
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! use axum::{
+//!     body::Body, extract::Request, middleware::Next, response::Response, routing::get, Extension,
+//!     Router,
+//! };
+//! use axum_thiserror_tracing::IntoResponse;
+//! use tower::ServiceExt;
+//! use thiserror::Error;
+//!
+//! #[derive(Debug, Error, IntoResponse)]
+//! pub enum AppError {
+//!     #[error("Database connection refused")]
+//!     Database,
+//! }
+//!
+//! #[derive(Clone, Copy)]
+//! pub struct MaskErrors(pub bool);
+//!
+//! // only trusted callers get to see the real error text
+//! async fn mask_errors(mut req: Request, next: Next) -> Response {
+//!     let trusted = req.headers().contains_key("x-internal-caller");
+//!     req.extensions_mut().insert(MaskErrors(!trusted));
+//!     next.run(req).await
+//! }
+//!
+//! async fn handler(Extension(MaskErrors(mask)): Extension<MaskErrors>) -> Response {
+//!     AppError::Database.into_response_masked(mask)
+//! }
+//!
+//! let app = Router::new()
+//!     .route("/", get(handler))
+//!     .layer(axum::middleware::from_fn(mask_errors));
+//!
+//! let req = Request::get("/").body(Body::empty()).unwrap();
+//! let res = app.clone().oneshot(req).await.unwrap();
+//! assert_eq!(res.status(), axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+//! let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+//! assert_eq!(body, "Something went wrong");
+//!
+//! let req = Request::get("/")
+//!     .header("x-internal-caller", "1")
+//!     .body(Body::empty())
+//!     .unwrap();
+//! let res = app.oneshot(req).await.unwrap();
+//! assert_eq!(res.status(), axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+//! let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+//! assert_eq!(body, "Database connection refused");
+//! # }
+//! ```
+//!
+//! A working example with all the features enabled can be viewed [in the repo](https://github.com/ozpv/axum_thiserror_intoresponse_derive/blob/main/example/src/main.rs)
+//!
+//! ## Contributing
+//!
+//! All contributions are welcome. Just open a pull request in the repo.
+//!
+//! ## License
+//!
+//! This lib is licensed under the [MIT license](https://github.com/ozpv/axum_thiserror_intoresponse_derive/blob/main/LICENSE)
+
+#![no_std]
+
+extern crate alloc;
+extern crate proc_macro;
+
+use alloc::{string::String, vec::Vec};
+use proc_macro::TokenStream;
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{
+    parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Expr, ExprLit, Fields,
+    Ident, Lit, LitStr, Meta, Path, Variant,
+};
+#[cfg(feature = "serde")]
+use syn::{punctuated::Punctuated, Token};
+
+/// attributes read from the enum
+const ENUM_ATTRIBUTES: &[&str] = &[
+    "allow_duplicate_status",
+    "doc_as_message",
+    "from_status",
+    "internal_display",
+    "internal_empty_body",
+    "internal_html",
+    "internal_text",
+    "internal_text_fn",
+    "internal_text_traced",
+    "json_case",
+    "message_fn",
+    "name_as_body",
+    "no_cache",
+    "openapi",
+    "reason_phrase_body",
+    "request_id_fn",
+    "response",
+    "response_fn",
+    "serde_cause",
+    "serde_field",
+    "skip_uncategorized",
+    "status",
+    "status_fn",
+    "status_from",
+    "status_table",
+    "trace_debug",
+];
+
+/// attributes read from a variant
+const VARIANT_ATTRIBUTES: &[&str] = &[
+    "body_bytes",
+    "cache_control",
+    "category",
+    "code",
+    "content_type",
+    "delegate",
+    "header",
+    "name_as_body",
+    "response",
+    "sensitive",
+    "serde_inline_field",
+    "status",
+    "text",
+];
+
+#[proc_macro_derive(
+    IntoResponse,
+    attributes(
+        allow_duplicate_status,
+        body_bytes,
+        cache_control,
+        category,
+        code,
+        content_type,
+        delegate,
+        doc_as_message,
+        from_status,
+        header,
+        internal_display,
+        internal_empty_body,
+        internal_html,
+        internal_text,
+        internal_text_fn,
+        internal_text_traced,
+        json_case,
+        message_fn,
+        name_as_body,
+        no_cache,
+        openapi,
+        reason_phrase_body,
+        request_id_fn,
+        response,
+        response_fn,
+        sensitive,
+        serde_cause,
+        serde_field,
+        serde_inline_field,
+        skip_uncategorized,
+        status,
+        status_fn,
+        status_from,
+        status_table,
+        text,
+        trace_debug
+    )
+)]
+pub fn derive_into_response(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let not_an_enum = match &input.data {
+        Data::Enum(_) => None,
+        Data::Struct(data) => Some(data.struct_token.to_token_stream()),
+        Data::Union(data) => Some(data.union_token.to_token_stream()),
+    };
+
+    if let Some(token) = not_an_enum {
+        return syn::Error::new_spanned(token, "IntoResponse can only be derived on an enum")
+            .to_compile_error()
+            .into();
+    }
+
+    let name = input.ident;
+
+    let attrs = &input.attrs;
+
+    // a misspelled or misplaced attribute would otherwise be ignored without a word
+    if let Err(err) = check_attributes(attrs, ENUM_ATTRIBUTES, VARIANT_ATTRIBUTES, "the enum") {
+        return err.to_compile_error().into();
+    }
+
+    // parse the internal_text, internal_text_traced and internal_html attributes
+    let internal_text = str_attr(attrs, "internal_text");
+    let internal_text_traced = str_attr(attrs, "internal_text_traced");
+    let internal_html = str_attr(attrs, "internal_html");
+
+    if let Some((attr, _)) = &internal_html {
+        if internal_text.is_some() || internal_text_traced.is_some() {
+            return syn::Error::new_spanned(
+                attr,
+                "#[internal_html] can't be combined with #[internal_text]",
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
+
+    // the tracing feature is this crate's, so pick the text here rather than in the generated code
+    #[cfg(feature = "tracing")]
+    let internal_text = internal_text_traced.or(internal_text);
+
+    let ignored_text_attr = internal_text.as_ref().map(|(attr, _)| *attr);
+
+    let internal_text = internal_text
+        .or(internal_html.clone())
+        .map(|(_, text)| text)
+        .unwrap_or_else(|| String::from("Something went wrong"));
+
+    // parse the internal_display attribute
+    let internal_display = attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("internal_display"));
+
+    // rendering the error text as html would let it inject markup
+    if let (Some((attr, _)), true) = (&internal_html, internal_display) {
+        return syn::Error::new_spanned(
+            attr,
+            "#[internal_html] can't be combined with #[internal_display]",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // parse the internal_empty_body attribute
+    let internal_empty_body = attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("internal_empty_body"));
+
+    // parse the internal_text_fn attribute
+    let internal_text_fn = match path_attr(attrs, "internal_text_fn") {
+        Ok(path) => path,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    if let (Some(path), true) = (&internal_text_fn, internal_empty_body) {
+        return syn::Error::new_spanned(
+            path,
+            "#[internal_text_fn] can't be combined with #[internal_empty_body]",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // the function's text isn't escaped, so it can't be sent as the html page
+    if let (Some(path), Some(_)) = (&internal_text_fn, &internal_html) {
+        return syn::Error::new_spanned(
+            path,
+            "#[internal_text_fn] can't be combined with #[internal_html]",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let internal_body = match &internal_text_fn {
+        Some(path) => quote! { #path() },
+        None if internal_empty_body => quote! { ::std::string::String::new() },
+        None => quote! { #internal_text.to_string() },
+    };
+
+    // there's no stable way to warn from a derive, so borrow the deprecation lint to point at
+    // the static text losing to the function
+    let ignored_internal_text = match (&internal_text_fn, &ignored_text_attr) {
+        (Some(_), Some(attr)) => quote_spanned! {attr.span()=>
+            const _: () = {
+                #[deprecated(note = "#[internal_text] is ignored when #[internal_text_fn] is set")]
+                struct InternalText;
+                let _ = InternalText;
+            };
+        },
+        _ => proc_macro2::TokenStream::new(),
+    };
+
+    // parse the message_fn attribute
+    let message_fn = match path_attr(attrs, "message_fn") {
+        Ok(path) => path,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // parse the status_fn attribute
+    let status_fn = match path_attr(attrs, "status_fn") {
+        Ok(path) => path,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // parse the enum-wide status
+    let enum_status = match status_attr(attrs, "enum") {
+        Ok(status) => status,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    if let (Some(_), Some(path)) = (&enum_status, &status_fn) {
+        return syn::Error::new_spanned(
+            path,
+            "#[status_fn] can't be combined with a #[status(...)] on the enum",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // parse the reason_phrase_body attribute
+    let reason_phrase_body = attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("reason_phrase_body"));
+
+    if let (Some(attr), Some(_)) = (reason_phrase_body, &message_fn) {
+        return syn::Error::new_spanned(
+            attr,
+            "#[reason_phrase_body] can't be combined with #[message_fn]",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let reason_phrase_body = reason_phrase_body.is_some();
+
+    let message = match &message_fn {
+        Some(path) => quote! { #path(self) },
+        None if reason_phrase_body => quote! {
+            ::std::string::String::from(self.status_code().canonical_reason().unwrap_or(""))
+        },
+        None => quote! { self.to_string() },
+    };
+
+    // parse the name_as_body attribute
+    let name_as_body = attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("name_as_body"));
+
+    // parse the doc_as_message attribute
+    let doc_as_message = attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("doc_as_message"));
+
+    // parse the serde_field attributes, keeping them in declaration order
+    #[cfg(feature = "serde")]
+    let serde_fields = {
+        let mut serde_fields = Vec::new();
+
+        for attr in attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("serde_field"))
+        {
+            let pair = attr
+                .parse_args_with(Punctuated::<LitStr, Token![,]>::parse_terminated)
+                .ok()
+                .filter(|pair| pair.len() == 2);
+
+            let Some(pair) = pair else {
+                return syn::Error::new_spanned(
+                    attr,
+                    "expected #[serde_field(\"key\", \"value\")]",
+                )
+                .to_compile_error()
+                .into();
+            };
+
+            serde_fields.push((pair[0].clone(), pair[1].clone()));
+        }
+
+        serde_fields
+    };
+
+    // parse the serde_cause attribute, leaving out `cause` instead of null with skip_none
+    #[cfg(feature = "serde")]
+    let (serde_cause, skip_causeless) = match attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("serde_cause"))
+    {
+        None => (false, false),
+        Some(attr) if matches!(attr.meta, Meta::Path(_)) => (true, false),
+        Some(attr) => match attr.parse_args::<Ident>() {
+            Ok(ident) if ident == "skip_none" => (true, true),
+            _ => {
+                return syn::Error::new_spanned(
+                    attr,
+                    "expected #[serde_cause] or #[serde_cause(skip_none)]",
+                )
+                .to_compile_error()
+                .into()
+            }
+        },
+    };
+
+    // parse the json_case attribute
+    #[cfg(feature = "serde")]
+    let json_case = match JsonCase::parse(attrs) {
+        Ok(case) => case,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // parse the enum-wide response representation
+    let default_representation = match representation_attr(attrs) {
+        Ok(representation) => representation.unwrap_or(Representation::Text),
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // parse the attributes for status code override (if any)
+    let mut variant_overrides = Vec::new();
+
+    // variants that override the enum-wide representation
+    let mut representation_overrides = Vec::new();
+
+    // #[delegate] variants, binding the inner error as `inner`
+    let mut delegates = Vec::new();
+
+    // variants with their own #[text = "..."] body
+    let mut text_overrides = Vec::new();
+
+    // the status and body of every overridden variant, to catch copy-pasted variants
+    let mut mappings: Vec<(String, String, &Ident)> = Vec::new();
+    let allow_duplicates = attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("allow_duplicate_status"));
+
+    // the status and body of every variant for response_examples, with a 500 showing the masked body
+    let mut examples = Vec::new();
+    let masked_example = if internal_empty_body {
+        String::new()
+    } else {
+        internal_text.clone()
+    };
+
+    // constructible unit variants and their #[status(...)] for from_status
+    let mut unit_statuses = Vec::new();
+
+    // the discriminant and status of unit variants for #[status_table]
+    let mut table_entries = Vec::new();
+
+    // variants with their own #[cache_control("...")]
+    let mut cache_control_overrides = Vec::new();
+
+    // variants with their own #[header("name", "value")]s
+    let mut header_overrides = Vec::new();
+
+    // #[body_bytes(path::to::fn)] variants and their content type
+    let mut body_bytes_overrides = Vec::new();
+
+    // the name of every variant for the tracing event
+    #[cfg(feature = "tracing")]
+    let mut variant_names = Vec::new();
+
+    // #[sensitive] variants, only logged by name and status
+    #[cfg(feature = "tracing")]
+    let mut sensitive_variants = Vec::new();
+
+    // #[category = "..."] of the serialized kind
+    #[cfg(feature = "serde")]
+    let mut categories = Vec::new();
+
+    // #[code("...")] of the serialized error_code, defaulting to the SCREAMING_SNAKE_CASE variant name
+    #[cfg(feature = "serde")]
+    let mut codes = Vec::new();
+    #[cfg(feature = "serde")]
+    let mut has_code = false;
+
+    // #[serde_inline_field] variants, binding the field serialized as the error as `inner`
+    #[cfg(feature = "serde")]
+    let mut inline_fields = Vec::new();
+
+    // the Display of the #[source] or #[from] field of every variant with one
+    #[cfg(feature = "serde")]
+    let mut causes = Vec::new();
+
+    let variant_count = match &input.data {
+        Data::Enum(data) => data.variants.len(),
+        _ => 0,
+    };
+
+    // only Display is needed, so #[error(...)] is only expected when thiserror provides it
+    let uses_thiserror = match &input.data {
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .flat_map(|variant| &variant.attrs)
+            .chain(attrs)
+            .any(|attr| attr.path().is_ident("error")),
+        _ => false,
+    };
+
+    // an #[error(fmt = ...)] on the enum displays every variant, thiserror checks the rest
+    let enum_error = attrs.iter().any(|attr| attr.path().is_ident("error"));
+
+    // carry lifetimes and type parameters into every generated impl. thiserror only implements
+    // Display where the type parameters allow it, so the impls need the same bound
+    let mut generics = input.generics.clone();
+    if generics.type_params().next().is_some() {
+        let (_, ty_generics, _) = input.generics.split_for_impl();
+        let predicate: syn::WherePredicate = if uses_thiserror {
+            syn::parse_quote! { #name #ty_generics: ::std::error::Error }
+        } else {
+            syn::parse_quote! { #name #ty_generics: ::core::fmt::Display + ::core::fmt::Debug }
+        };
+        generics.make_where_clause().predicates.push(predicate);
+    }
+    let generics = &generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    if let Data::Enum(data) = &input.data {
+        for variant in &data.variants {
+            let name = &variant.ident;
+
+            if let Err(err) = check_attributes(
+                &variant.attrs,
+                VARIANT_ATTRIBUTES,
+                ENUM_ATTRIBUTES,
+                "a variant",
+            ) {
+                return err.to_compile_error().into();
+            }
+
+            // make sure fields on the enum variants are matched
+            let fields = match &variant.fields {
+                Fields::Named(_) => quote! {{..}},
+                Fields::Unit => quote! {},
+                Fields::Unnamed(fields) => {
+                    let all_fields = fields
+                        .unnamed
+                        .iter()
+                        .map(|_| quote! {_})
+                        .collect::<Vec<proc_macro2::TokenStream>>();
+
+                    quote! {
+                        (#(#all_fields),*)
+                    }
+                }
+            };
+
+            if variant
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("delegate"))
+            {
+                let pattern = match delegate_pattern(variant) {
+                    Ok(pattern) => pattern,
+                    Err(err) => return err.to_compile_error().into(),
+                };
+
+                variant_overrides.push(quote! {
+                    Self::#name #pattern => inner.status_code(),
+                });
+                delegates.push(quote! { Self::#name #pattern });
+
+                continue;
+            }
+
+            match representation_attr(&variant.attrs) {
+                Ok(Some(representation)) if representation != default_representation => {
+                    representation_overrides.push(quote! {
+                        Self::#name #fields => #representation,
+                    });
+                }
+                Ok(_) => {}
+                Err(err) => return err.to_compile_error().into(),
+            }
+
+            #[cfg(feature = "tracing")]
+            {
+                let variant_name = alloc::format!("{name}");
+                variant_names.push(quote! {
+                    Self::#name #fields => #variant_name,
+                });
+
+                if variant
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path().is_ident("sensitive"))
+                {
+                    sensitive_variants.push(quote! { Self::#name #fields });
+                }
+            }
+
+            #[cfg(feature = "serde")]
+            if let Some((_, category)) = str_attr(&variant.attrs, "category") {
+                categories.push(quote! {
+                    Self::#name #fields => Some(#category),
+                });
+            }
+
+            #[cfg(feature = "serde")]
+            {
+                let code = match variant
+                    .attrs
+                    .iter()
+                    .find(|attr| attr.path().is_ident("code"))
+                    .map(|attr| attr.parse_args::<LitStr>())
+                {
+                    Some(Ok(code)) => {
+                        has_code = true;
+                        code.value()
+                    }
+                    Some(Err(err)) => return err.to_compile_error().into(),
+                    None => screaming_snake_case(&alloc::format!("{name}")),
+                };
+
+                codes.push(quote! {
+                    Self::#name #fields => #code,
+                });
+            }
+
+            #[cfg(feature = "serde")]
+            if let (true, Some(pattern)) = (serde_cause, cause_pattern(variant)) {
+                causes.push(quote! {
+                    Self::#name #pattern => Some(cause.to_string()),
+                });
+            }
+
+            // checked without the serde feature too, so enabling it doesn't surface new errors
+            if variant
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("serde_inline_field"))
+            {
+                let pattern = match inner_pattern(variant, "serde_inline_field") {
+                    Ok(pattern) => pattern,
+                    Err(err) => return err.to_compile_error().into(),
+                };
+
+                #[cfg(feature = "serde")]
+                inline_fields.push(quote! { Self::#name #pattern });
+                #[cfg(not(feature = "serde"))]
+                let _ = pattern;
+            }
+
+            let cache_control = variant
+                .attrs
+                .iter()
+                .find(|attr| attr.path().is_ident("cache_control"));
+
+            if let Some(attr) = cache_control {
+                let value = match header_value_attr(attr) {
+                    Ok(value) => value,
+                    Err(err) => return err.to_compile_error().into(),
+                };
+
+                cache_control_overrides.push(quote! {
+                    Self::#name #fields => Some(#value),
+                });
+            }
+
+            let body_bytes = match path_attr(&variant.attrs, "body_bytes") {
+                Ok(path) => path,
+                Err(err) => return err.to_compile_error().into(),
+            };
+
+            let content_type = variant
+                .attrs
+                .iter()
+                .find(|attr| attr.path().is_ident("content_type"));
+
+            let content_type = match (content_type.map(header_value_attr), &body_bytes) {
+                (Some(Err(err)), _) => return err.to_compile_error().into(),
+                (Some(Ok(content_type)), None) => {
+                    return syn::Error::new_spanned(
+                        content_type,
+                        "#[content_type(...)] only applies to #[body_bytes(...)] variants",
+                    )
+                    .to_compile_error()
+                    .into()
+                }
+                (Some(Ok(content_type)), Some(_)) => content_type,
+                (None, _) => LitStr::new("application/octet-stream", name.span()),
+            };
+
+            if let Some(path) = body_bytes {
+                body_bytes_overrides.push(quote! {
+                    Self::#name #fields => {
+                        return ::axum::response::IntoResponse::into_response((
+                            status,
+                            [(
+                                ::axum::http::header::CONTENT_TYPE,
+                                ::axum::http::HeaderValue::from_static(#content_type),
+                            )],
+                            #path(self),
+                        ));
+                    }
+                });
+            }
+
+            let mut headers = Vec::new();
+
+            for attr in variant
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("header"))
+            {
+                let (header, value) = match header_attr(attr) {
+                    Ok(header) => header,
+                    Err(err) => return err.to_compile_error().into(),
+                };
+
+                headers.push(quote! {
+                    response.headers_mut().append(
+                        ::axum::http::HeaderName::from_static(#header),
+                        ::axum::http::HeaderValue::from_static(#value),
+                    );
+                });
+            }
+
+            if !headers.is_empty() {
+                header_overrides.push(quote! {
+                    Self::#name #fields => {
+                        #(#headers)*
+                    }
+                });
+            }
+
+            let text = variant
+                .attrs
+                .iter()
+                .find(|attr| attr.path().is_ident("text"));
+
+            let has_error = variant
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("error"));
+
+            // without an #[error(...)], fall back to the first line of the doc comment
+            let doc_message = doc_as_message && !has_error;
+
+            let name_as_body = name_as_body
+                || variant
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path().is_ident("name_as_body"));
+
+            if let Some(attr) = text {
+                let arm = match text_arm(attr, variant) {
+                    Ok(arm) => arm,
+                    Err(err) => return err.to_compile_error().into(),
+                };
+
+                text_overrides.push(arm);
+            } else if name_as_body {
+                text_overrides.push(quote! {
+                    Self::#name #fields => ::std::string::String::from(::core::stringify!(#name)),
+                });
+            } else if doc_message {
+                let doc = str_attr(&variant.attrs, "doc")
+                    .and_then(|(_, doc)| doc.lines().next().map(|line| String::from(line.trim())))
+                    .unwrap_or_else(|| internal_text.clone());
+
+                text_overrides.push(quote! {
+                    Self::#name #fields => ::std::string::String::from(#doc),
+                });
+            }
+
+            let status = match status_attr(&variant.attrs, "variant") {
+                Ok(status) => status,
+                Err(err) => return err.to_compile_error().into(),
+            };
+
+            let message = example_message(variant, name_as_body);
+            let internal_example = if internal_display {
+                message.clone()
+            } else {
+                masked_example.clone()
+            };
+
+            let message = if reason_phrase_body && text.is_none() && !name_as_body && !doc_message {
+                quote! { status.canonical_reason().unwrap_or("") }
+            } else {
+                quote! { #message }
+            };
+
+            // the enum's #[status(...)] is the status of every variant without one
+            match status.as_ref().or(enum_status.as_ref()) {
+                Some(status) => examples.push(quote! {
+                    {
+                        let status: ::axum::http::StatusCode = {
+                            #[allow(unused_imports)]
+                            use ::axum::http::StatusCode;
+                            #status
+                        };
+
+                        if status == ::axum::http::StatusCode::INTERNAL_SERVER_ERROR {
+                            (status.as_u16(), #internal_example)
+                        } else {
+                            (status.as_u16(), #message)
+                        }
+                    }
+                }),
+                None if status_fn.is_none() => examples.push(quote! { (500, #internal_example) }),
+                None => {}
+            }
+
+            if let Some(status) = status {
+                // the body of an overridden status comes from Display, so make sure thiserror has
+                // something to display before the error shows up far away in the generated code
+                if uses_thiserror
+                    && !enum_error
+                    && message_fn.is_none()
+                    && text.is_none()
+                    && !name_as_body
+                    && !reason_phrase_body
+                    && !doc_message
+                    && !has_error
+                {
+                    return syn::Error::new_spanned(
+                        name,
+                        "variants with #[status(...)] need an #[error(...)] to use as the response body",
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+
+                // only compare bodies that are the same on every response, a message_fn, a
+                // placeholder or #[error(transparent)] can all differ at runtime
+                let body = if variant
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path().is_ident("body_bytes"))
+                {
+                    // the bytes come from a function, so they can differ like a message_fn
+                    None
+                } else if text.is_some() {
+                    str_attr(&variant.attrs, "text").and_then(|(_, text)| fixed_body(&text))
+                } else if !name_as_body && !reason_phrase_body && message_fn.is_none() {
+                    variant
+                        .attrs
+                        .iter()
+                        .find(|attr| attr.path().is_ident("error"))
+                        .and_then(|attr| {
+                            attr.parse_args_with(|input: syn::parse::ParseStream| {
+                                let format = input.parse::<LitStr>()?;
+                                // format arguments after the string are filled in at runtime
+                                let rest = input.parse::<proc_macro2::TokenStream>()?;
+                                Ok(rest.is_empty().then(|| format.value()))
+                            })
+                            .ok()
+                            .flatten()
+                        })
+                        .and_then(|format| fixed_body(&format))
+                } else {
+                    None
+                };
+
+                if let (Some(body), false) = (body, allow_duplicates) {
+                    // clients can also tell variants apart by their error code, headers or content type
+                    let key = variant
+                        .attrs
+                        .iter()
+                        .filter(|attr| {
+                            ["code", "header", "response"]
+                                .iter()
+                                .any(|ident| attr.path().is_ident(ident))
+                        })
+                        .fold(
+                            alloc::format!("{}", status.to_token_stream()),
+                            |key, attr| alloc::format!("{key} {}", attr.to_token_stream()),
+                        );
+
+                    if let Some((_, _, first)) =
+                        mappings.iter().find(|(other_status, other_body, _)| {
+                            *other_status == key && *other_body == body
+                        })
+                    {
+                        return syn::Error::new_spanned(
+                            name,
+                            alloc::format!(
+                                "`{name}` has the same status and body as `{first}`, add #[allow_duplicate_status] to the enum if that's intended"
+                            ),
+                        )
+                        .to_compile_error()
+                        .into();
+                    }
+
+                    mappings.push((key, body, name));
+                }
+
+                if let Fields::Unit = variant.fields {
+                    table_entries.push(quote! {
+                        (Self::#name as u16, {
+                            #[allow(unused_imports)]
+                            use ::axum::http::StatusCode;
+                            #status
+                        }.as_u16())
+                    });
+
+                    unit_statuses.push(quote! {
+                        let status: ::axum::http::StatusCode = {
+                            #[allow(unused_imports)]
+                            use ::axum::http::StatusCode;
+                            #status
+                        };
+
+                        if code == status {
+                            return Some(Self::#name);
+                        }
+                    });
+                }
+
+                // resolve the expression like the user's own code would, with a bare
+                // `StatusCode` still working when it isn't imported
+                let status = quote! {
+                    Self::#name #fields => {
+                        #[allow(unused_imports)]
+                        use ::axum::http::StatusCode;
+                        #status
+                    }
+                };
+
+                variant_overrides.push(status);
+            }
+        }
+    }
+
+    let tracing = {
+        #[allow(unused)]
+        let mut stream = proc_macro2::TokenStream::new();
+        #[cfg(feature = "tracing")]
+        {
+            let variant_fallback = fallback_arm(variant_names.len(), variant_count, quote! { "" });
+
+            // the Debug output keeps the whole source chain
+            let event = if attrs.iter().any(|attr| attr.path().is_ident("trace_debug")) {
+                quote! {
+                    ::tracing::error!(status = %status.as_u16(), variant, error = ?self, "request failed");
+                }
+            } else {
+                quote! {
+                    let internal_err = self.__into_response_traced_error();
+                    ::tracing::error!(status = %status.as_u16(), variant, error = %internal_err, "request failed");
+                }
+            };
+
+            // keep the fields of #[sensitive] variants out of the logs
+            let event = if sensitive_variants.is_empty() {
+                event
+            } else {
+                quote! {
+                    if matches!(self, #(#sensitive_variants)|*) {
+                        ::tracing::error!(status = %status.as_u16(), variant, "request failed");
+                    } else {
+                        #event
+                    }
+                }
+            };
+
+            let err = quote! {
+                let variant = match self {
+                    #(#variant_names)*
+                    #variant_fallback
+                };
+                #event
+            };
+
+            stream = err;
+        }
+        stream
+    };
+
+    // with thiserror the type is an Error, so the message can carry the source chain. A boxed error
+    // behind #[from] is skipped where the outer message already ends with it, as with #[error("{0}")]
+    let traced_error = {
+        #[allow(unused)]
+        let mut stream = proc_macro2::TokenStream::new();
+        #[cfg(feature = "tracing")]
+        if !attrs.iter().any(|attr| attr.path().is_ident("trace_debug")) {
+            let sources = if uses_thiserror {
+                quote! {
+                    let mut source = ::std::error::Error::source(self);
+
+                    while let Some(err) = source {
+                        let cause = err.to_string();
+                        if !message.ends_with(&cause) {
+                            message.push_str(": ");
+                            message.push_str(&cause);
+                        }
+                        source = err.source();
+                    }
+                }
+            } else {
+                proc_macro2::TokenStream::new()
+            };
+
+            stream = quote! {
+                fn __into_response_traced_error(&self) -> ::std::string::String {
+                    #[allow(unused_mut)]
+                    let mut message = self.to_string();
+                    #sources
+                    message
+                }
+            };
+        }
+        stream
+    };
+
+    // pick between a json and plain text body per variant
+    let representation = if default_representation != Representation::Text
+        || !representation_overrides.is_empty()
+    {
+        let json = if cfg!(feature = "serde") {
+            quote! {
+                Representation::Json => {
+                    return self.__into_response_json(status, mask);
+                }
+            }
+        } else {
+            proc_macro2::TokenStream::new()
+        };
+
+        let representation_fallback = fallback_arm(
+            representation_overrides.len(),
+            variant_count,
+            quote! { #default_representation },
+        );
+
+        quote! {
+            #[allow(dead_code)]
+            enum Representation {
+                Text,
+                Json,
+                RawJson,
+            }
+
+            let representation = match self {
+                #(#representation_overrides)*
+                #representation_fallback
+            };
+
+            match representation {
+                #json
+                Representation::RawJson => {
+                    // push the text as a json string without pulling in a serializer
+                    fn escape(text: &str, out: &mut ::std::string::String) {
+                        for c in text.chars() {
+                            match c {
+                                '"' => out.push_str("\\\""),
+                                '\\' => out.push_str("\\\\"),
+                                '\n' => out.push_str("\\n"),
+                                '\r' => out.push_str("\\r"),
+                                '\t' => out.push_str("\\t"),
+                                '\u{08}' => out.push_str("\\b"),
+                                '\u{0c}' => out.push_str("\\f"),
+                                c if c < ' ' => {
+                                    out.push_str(&::std::format!("\\u{:04x}", c as u32));
+                                }
+                                c => out.push(c),
+                            }
+                        }
+                    }
+
+                    let mut body = ::std::format!("{{\"status\":{},\"error\":\"", status.as_u16());
+                    escape(&self.response_text(mask), &mut body);
+                    body.push_str("\"}");
+
+                    return ::axum::response::IntoResponse::into_response((
+                        status,
+                        [(::axum::http::header::CONTENT_TYPE, "application/json")],
+                        body,
+                    ));
+                }
+                _ => {}
+            }
+        }
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+
+    // serve the masked 500 body as html
+    let html = if internal_html.is_some() && !internal_empty_body {
+        quote! {
+            if mask && status == ::axum::http::StatusCode::INTERNAL_SERVER_ERROR {
+                return ::axum::response::IntoResponse::into_response((
+                    status,
+                    ::axum::response::Html(self.response_text(mask)),
+                ));
+            }
+        }
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+
+    let status_default = match (&enum_status, &status_fn) {
+        (Some(status), _) => quote! {
+            {
+                #[allow(unused_imports)]
+                use ::axum::http::StatusCode;
+                #status
+            }
+        },
+        (None, Some(path)) => quote! { #path(self) },
+        (None, None) => quote! { ::axum::http::StatusCode::INTERNAL_SERVER_ERROR },
+    };
+
+    // fieldless enums can look their status up by discriminant instead of matching on every variant
+    let fieldless = match &input.data {
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .all(|variant| matches!(variant.fields, Fields::Unit)),
+        _ => false,
+    };
+    let status_table = variant_count > 0
+        && fieldless
+        && attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("status_table"));
+
+    // an empty enum can't be constructed, so there's nothing to match on
+    let status_lookup = if variant_count == 0 {
+        quote! { match *self {} }
+    } else if status_table {
+        quote! {
+            let discriminant = *self as u16;
+
+            // declared in order of their discriminant, which is the usual case, the table is
+            // binary searched. Otherwise it's scanned, which is still just a few u16 pairs
+            let entry = if Self::__INTO_RESPONSE_STATUS_CODES_SORTED {
+                Self::STATUS_CODES
+                    .binary_search_by_key(&discriminant, |(variant, _)| *variant)
+                    .ok()
+                    .map(|i| &Self::STATUS_CODES[i])
+            } else {
+                Self::STATUS_CODES.iter().find(|(variant, _)| *variant == discriminant)
+            };
+
+            match entry {
+                Some((_, status)) => ::axum::http::StatusCode::from_u16(*status)
+                    .unwrap_or(::axum::http::StatusCode::INTERNAL_SERVER_ERROR),
+                None => #status_default,
+            }
+        }
+    } else {
+        // a catch-all next to an arm for every variant trips unreachable_patterns downstream
+        let status_fallback = fallback_arm(variant_overrides.len(), variant_count, status_default);
+
+        quote! {
+            match self {
+                #(#variant_overrides)*
+                #status_fallback
+            }
+        }
+    };
+
+    let status_codes = if status_table {
+        let variants = match &input.data {
+            Data::Enum(data) => data.variants.iter().map(|variant| &variant.ident).collect(),
+            _ => Vec::new(),
+        };
+
+        quote! {
+            /// The discriminant and status of every variant with a `#[status(...)]`
+            pub const STATUS_CODES: &'static [(u16, u16)] = {
+                // a wider discriminant would be cut down to a u16 and look up another variant
+                #(
+                    ::core::assert!(
+                        Self::#variants as i128 >= 0 && Self::#variants as u128 <= u16::MAX as u128,
+                        "#[status_table] needs every discriminant to fit in a u16",
+                    );
+                )*
+
+                &[#(#table_entries),*]
+            };
+
+            const __INTO_RESPONSE_STATUS_CODES_SORTED: bool = {
+                let mut i = 1;
+                while i < Self::STATUS_CODES.len() {
+                    if Self::STATUS_CODES[i - 1].0 >= Self::STATUS_CODES[i].0 {
+                        break;
+                    }
+                    i += 1;
+                }
+                i >= Self::STATUS_CODES.len()
+            };
+        }
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+    let message_fallback = fallback_arm(text_overrides.len(), variant_count, message);
+
+    // a 500 hides the bytes like the text
+    let body_bytes = if body_bytes_overrides.is_empty() {
+        proc_macro2::TokenStream::new()
+    } else {
+        let body_bytes_fallback =
+            fallback_arm(body_bytes_overrides.len(), variant_count, quote! { {} });
+
+        quote! {
+            if #internal_display || !mask || status != ::axum::http::StatusCode::INTERNAL_SERVER_ERROR {
+                match self {
+                    #(#body_bytes_overrides)*
+                    #body_bytes_fallback
+                }
+            }
+        }
+    };
+
+    let headers = if header_overrides.is_empty() {
+        proc_macro2::TokenStream::new()
+    } else {
+        let header_fallback = fallback_arm(header_overrides.len(), variant_count, quote! { {} });
+
+        quote! {
+            match self {
+                #(#header_overrides)*
+                #header_fallback
+            }
+        }
+    };
+
+    // parse the response_fn attribute, handing into_response to the function
+    let into_response = match path_attr(attrs, "response_fn") {
+        Ok(Some(path)) => quote! { #path(self) },
+        Ok(None) => quote! { self.into_response_masked(true) },
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // parse the request_id_fn attribute
+    let request_id = match path_attr(attrs, "request_id_fn") {
+        Ok(Some(path)) => quote! {
+            // an id that isn't a valid header value is left out rather than failing the response
+            if let Some(request_id) = #path() {
+                if let Ok(value) = ::axum::http::HeaderValue::try_from(request_id) {
+                    response.headers_mut().insert("x-request-id", value);
+                }
+            }
+        },
+        Ok(None) => proc_macro2::TokenStream::new(),
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // a variant's #[cache_control("...")] wins over the enum's #[no_cache]
+    let no_cache = attrs.iter().any(|attr| attr.path().is_ident("no_cache"));
+
+    let cache_control = if no_cache || !cache_control_overrides.is_empty() {
+        let cache_control_fallback = fallback_arm(
+            cache_control_overrides.len(),
+            variant_count,
+            if no_cache {
+                quote! { Some("no-store") }
+            } else {
+                quote! { None }
+            },
+        );
+
+        quote! {
+            let cache_control: Option<&'static str> = match self {
+                #(#cache_control_overrides)*
+                #cache_control_fallback
+            };
+
+            if let Some(cache_control) = cache_control {
+                response.headers_mut().insert(
+                    ::axum::http::header::CACHE_CONTROL,
+                    ::axum::http::HeaderValue::from_static(cache_control),
+                );
+            }
+        }
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+
+    // #[delegate] variants hand everything to the inner error
+    let (delegate_text, delegate_response, delegate_text_response) = if delegates.is_empty() {
+        (
+            proc_macro2::TokenStream::new(),
+            proc_macro2::TokenStream::new(),
+            proc_macro2::TokenStream::new(),
+        )
+    } else {
+        (
+            quote! {
+                match self {
+                    #(#delegates => return inner.response_text(mask),)*
+                    _ => {}
+                }
+            },
+            quote! {
+                match self {
+                    #(#delegates => return inner.to_response_masked(mask),)*
+                    _ => {}
+                }
+            },
+            quote! {
+                match self {
+                    #(#delegates => return inner.into_response_text(),)*
+                    _ => {}
+                }
+            },
+        )
+    };
+
+    // build the impl
+    #[allow(unused_mut)]
+    let mut expanded = quote! {
+        #ignored_internal_text
+
+        #[automatically_derived]
+        impl #impl_generics ::axum::response::IntoResponse for #name #ty_generics #where_clause {
+            fn into_response(self) -> ::axum::response::Response {
+                #into_response
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics #name #ty_generics #where_clause {
+            #status_codes
+
+            /// Returns the status code the error responds with
+            #[must_use]
+            pub fn status_code(&self) -> ::axum::http::StatusCode {
+                #status_lookup
+            }
+
+            /// Returns the text of the response body, only hiding the error text of a 500 status
+            /// behind `internal_text` when `mask` is true
+            #[must_use]
+            pub fn response_text(&self, mask: bool) -> ::std::string::String {
+                #delegate_text
+
+                if mask
+                    && !#internal_display
+                    && self.status_code() == ::axum::http::StatusCode::INTERNAL_SERVER_ERROR
+                {
+                    #internal_body
+                } else {
+                    match self {
+                        #(#text_overrides)*
+                        #message_fallback
+                    }
+                }
+            }
+
+            /// Builds the same response as `into_response` without consuming the error
+            #[must_use]
+            pub fn to_response(&self) -> ::axum::response::Response {
+                self.to_response_masked(true)
+            }
+
+            /// Builds the response, only hiding the error text of a 500 status behind
+            /// `internal_text` when `mask` is true
+            #[must_use]
+            pub fn into_response_masked(self, mask: bool) -> ::axum::response::Response {
+                self.to_response_masked(mask)
+            }
+
+            /// Builds the same response as `into_response_masked` without consuming the error
+            #[must_use]
+            pub fn to_response_masked(&self, mask: bool) -> ::axum::response::Response {
+                #delegate_response
+
+                let status = self.status_code();
+
+                if status == ::axum::http::StatusCode::INTERNAL_SERVER_ERROR {
+                    #tracing
+                }
+
+                let mut response = self.__into_response_body(status, mask);
+                self.__into_response_finish(&mut response);
+                response
+            }
+
+            /// Builds the response with a plain text body, even where `#[response(...)]` or
+            /// `#[internal_html]` would pick another one
+            #[must_use]
+            pub fn into_response_text(self) -> ::axum::response::Response {
+                #delegate_text_response
+
+                let status = self.status_code();
+
+                if status == ::axum::http::StatusCode::INTERNAL_SERVER_ERROR {
+                    #tracing
+                }
+
+                let mut response =
+                    ::axum::response::IntoResponse::into_response((status, self.response_text(true)));
+                self.__into_response_finish(&mut response);
+                response
+            }
+
+            fn __into_response_body(
+                &self,
+                status: ::axum::http::StatusCode,
+                mask: bool,
+            ) -> ::axum::response::Response {
+                #body_bytes
+
+                #representation
+
+                #html
+
+                ::axum::response::IntoResponse::into_response((status, self.response_text(mask)))
+            }
+
+            #traced_error
+
+            fn __into_response_finish(&self, response: &mut ::axum::response::Response) {
+                #cache_control
+                #headers
+                #request_id
+
+                // 1xx, 204 and 304 responses must not have a body
+                let status = response.status();
+                if status.is_informational()
+                    || status == ::axum::http::StatusCode::NO_CONTENT
+                    || status == ::axum::http::StatusCode::NOT_MODIFIED
+                {
+                    *response.body_mut() = ::axum::body::Body::empty();
+                    response.headers_mut().remove(::axum::http::header::CONTENT_TYPE);
+                    response.headers_mut().remove(::axum::http::header::CONTENT_LENGTH);
+                }
+            }
+        }
+    };
+
+    if attrs.iter().any(|attr| attr.path().is_ident("status_from")) {
+        expanded.extend([quote! {
+            #[automatically_derived]
+            impl #impl_generics ::core::convert::From<&#name #ty_generics> for ::axum::http::StatusCode #where_clause {
+                fn from(err: &#name #ty_generics) -> Self {
+                    err.status_code()
+                }
+            }
+        }]);
+    }
+
+    if attrs.iter().any(|attr| attr.path().is_ident("from_status")) {
+        expanded.extend([quote! {
+            #[automatically_derived]
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Returns the first unit variant declared with the status, if any
+                #[must_use]
+                pub fn from_status(code: ::axum::http::StatusCode) -> Option<Self> {
+                    #(#unit_statuses)*
+                    None
+                }
+            }
+        }]);
+    }
+
+    if attrs.iter().any(|attr| attr.path().is_ident("openapi")) {
+        expanded.extend([quote! {
+            #[automatically_derived]
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Returns the status and an example body of every variant, in declaration order
+                #[must_use]
+                pub fn response_examples() -> ::std::vec::Vec<(u16, &'static str)> {
+                    ::std::vec![#(#examples),*]
+                }
+            }
+        }]);
+    }
+
+    #[cfg(feature = "serde")]
+    {
+        let envelope = Envelope {
+            serde_fields,
+            json_case,
+            categories,
+            skip_uncategorized: attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("skip_uncategorized")),
+            codes: if has_code { codes } else { Vec::new() },
+            delegates: delegates.clone(),
+            inline_fields,
+            causes: serde_cause.then_some((causes, skip_causeless)),
+            internal_display,
+            variant_count,
+        };
+
+        let ser = serde_derive(&name, generics, &envelope);
+        expanded.extend([ser]);
+    }
+
+    #[cfg(feature = "bincode")]
+    {
+        let bin = bincode_derive(&name, generics, &tracing);
+        expanded.extend([bin]);
+    }
+
+    expanded.into()
+}
+
+/// the `_ => value` arm of a generated match, left out once every variant has an arm of its own
+fn fallback_arm(
+    arms: usize,
+    variant_count: usize,
+    value: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if arms == variant_count && variant_count > 0 {
+        proc_macro2::TokenStream::new()
+    } else {
+        quote! { _ => #value, }
+    }
+}
+
+/// how the body of a response is represented
+#[derive(Clone, Copy, PartialEq)]
+enum Representation {
+    Text,
+    Json,
+    RawJson,
+}
+
+impl quote::ToTokens for Representation {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        // the variant of the `Representation` enum in the generated code
+        tokens.extend(match self {
+            Representation::Text => quote! { Representation::Text },
+            Representation::Json => quote! { Representation::Json },
+            Representation::RawJson => quote! { Representation::RawJson },
+        });
+    }
+}
 
-#![no_std]
+/// parses a `#[response(...)]` attribute
+fn representation_attr(attrs: &[Attribute]) -> syn::Result<Option<Representation>> {
+    let Some(attr) = attrs.iter().find(|attr| attr.path().is_ident("response")) else {
+        return Ok(None);
+    };
 
-extern crate alloc;
-extern crate proc_macro;
+    let kind = attr.parse_args::<Ident>()?;
 
-use alloc::{string::String, vec::Vec};
-use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Expr, Fields, Lit, Meta};
+    if kind == "text" {
+        Ok(Some(Representation::Text))
+    } else if kind == "json" {
+        if cfg!(feature = "serde") {
+            Ok(Some(Representation::Json))
+        } else {
+            Err(syn::Error::new_spanned(
+                kind,
+                "#[response(json)] requires the crate's serde feature",
+            ))
+        }
+    } else if kind == "raw_json" {
+        Ok(Some(Representation::RawJson))
+    } else {
+        Err(syn::Error::new_spanned(
+            kind,
+            "expected #[response(text)], #[response(json)] or #[response(raw_json)]",
+        ))
+    }
+}
 
-/// # Panics
-///
-/// if the type isn't an enum
-#[proc_macro_derive(IntoResponse, attributes(internal_text, status))]
-pub fn derive_into_response(input: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(input as DeriveInput);
+/// builds the match arm of a `#[text = "..."]` attribute, binding the named fields it refers to
+fn text_arm(attr: &Attribute, variant: &Variant) -> syn::Result<proc_macro2::TokenStream> {
+    let Meta::NameValue(meta) = &attr.meta else {
+        return Err(syn::Error::new_spanned(attr, "expected #[text = \"...\"]"));
+    };
 
-    let name = input.ident;
+    let Expr::Lit(ExprLit {
+        lit: Lit::Str(text),
+        ..
+    }) = &meta.value
+    else {
+        return Err(syn::Error::new_spanned(attr, "expected #[text = \"...\"]"));
+    };
 
-    let attrs = &input.attrs;
+    // collect the names between the braces, skipping escaped `{{`
+    let value = text.value();
+    let mut referenced = Vec::new();
+    let mut chars = value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '{' {
+            continue;
+        }
+
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            continue;
+        }
+
+        let mut name = String::new();
+        for c in chars.by_ref() {
+            if c == '}' || c == ':' {
+                break;
+            }
+            name.push(c);
+        }
+
+        let name = String::from(name.trim());
+        if !referenced.contains(&name) {
+            referenced.push(name);
+        }
+    }
+
+    let variant_name = &variant.ident;
+
+    if referenced.is_empty() {
+        let pattern = match &variant.fields {
+            Fields::Named(_) => quote! {{..}},
+            Fields::Unnamed(_) => quote! {(..)},
+            Fields::Unit => quote! {},
+        };
+
+        // nothing is formatted, so the escaped braces are unescaped here like format! would
+        let text = unescape_braces(&value);
+
+        return Ok(quote! {
+            Self::#variant_name #pattern => ::std::string::String::from(#text),
+        });
+    }
+
+    let Fields::Named(fields) = &variant.fields else {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "#[text = \"...\"] can only refer to fields of a variant with named fields",
+        ));
+    };
+
+    let mut bindings = Vec::new();
+
+    for name in &referenced {
+        let field = fields
+            .named
+            .iter()
+            .filter_map(|field| field.ident.as_ref())
+            .find(|ident| *ident == name);
+
+        let Some(field) = field else {
+            return Err(syn::Error::new_spanned(
+                text,
+                alloc::format!("variant `{variant_name}` has no field named `{name}`"),
+            ));
+        };
+
+        bindings.push(field);
+    }
+
+    Ok(quote! {
+        Self::#variant_name { #(#bindings,)* .. } => ::std::format!(#text, #(#bindings = #bindings),*),
+    })
+}
+
+/// binds the single field of a `#[delegate]` variant as `inner`
+fn delegate_pattern(variant: &Variant) -> syn::Result<proc_macro2::TokenStream> {
+    if let Some(attr) = variant
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("status"))
+    {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "#[delegate] variants take their status from the inner error",
+        ));
+    }
+
+    // the inner error builds the whole response, so the variant's own attributes would be ignored
+    for attr in &variant.attrs {
+        if let Some(ident) = attr.path().get_ident() {
+            if ident != "delegate" && VARIANT_ATTRIBUTES.iter().any(|known| ident == known) {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    alloc::format!(
+                        "#[{ident}] has no effect on a #[delegate] variant, the inner error builds the response"
+                    ),
+                ));
+            }
+        }
+    }
+
+    inner_pattern(variant, "delegate")
+}
+
+/// binds the single field of a variant as `inner`
+fn inner_pattern(variant: &Variant, attribute: &str) -> syn::Result<proc_macro2::TokenStream> {
+    match &variant.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Ok(quote! { (inner) }),
+        Fields::Named(fields) if fields.named.len() == 1 => {
+            let field = &fields.named[0].ident;
+            Ok(quote! { { #field: inner } })
+        }
+        _ => Err(syn::Error::new_spanned(
+            &variant.ident,
+            alloc::format!("#[{attribute}] variants must have exactly one field"),
+        )),
+    }
+}
+
+/// parses a `#[name("...")]` attribute holding a header value
+fn header_value_attr(attr: &Attribute) -> syn::Result<LitStr> {
+    header_value(attr.parse_args::<LitStr>()?)
+}
+
+/// parses a `#[header("name", "value")]` attribute, lowercasing the name for `HeaderName::from_static`
+fn header_attr(attr: &Attribute) -> syn::Result<(LitStr, LitStr)> {
+    let (name, value) = attr.parse_args_with(|input: syn::parse::ParseStream| {
+        let name = input.parse::<LitStr>()?;
+        input.parse::<syn::Token![,]>()?;
+        let value = input.parse::<LitStr>()?;
+        Ok((name, value))
+    })?;
+
+    let lowercase = name.value().to_ascii_lowercase();
+
+    if lowercase.is_empty()
+        || !lowercase
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+    {
+        return Err(syn::Error::new_spanned(name, "invalid header name"));
+    }
+
+    Ok((LitStr::new(&lowercase, name.span()), header_value(value)?))
+}
 
-    // parse the internal_text attribute
-    let internal_text = attrs
+/// checks a header value before it reaches the generated code
+fn header_value(value: LitStr) -> syn::Result<LitStr> {
+    // HeaderValue::from_static would panic on these at runtime
+    if !value
+        .value()
+        .bytes()
+        .all(|b| b == b'\t' || (b' '..=b'~').contains(&b))
+    {
+        return Err(syn::Error::new_spanned(
+            value,
+            "header values may only contain visible ASCII characters",
+        ));
+    }
+
+    Ok(value)
+}
+
+/// rejects attributes of this crate in the wrong place, and unknown ones that are a typo away from one
+fn check_attributes(
+    attrs: &[Attribute],
+    allowed: &[&str],
+    elsewhere: &[&str],
+    place: &str,
+) -> syn::Result<()> {
+    for attr in attrs {
+        let Some(ident) = attr.path().get_ident() else {
+            continue;
+        };
+        let ident = alloc::format!("{ident}");
+
+        if allowed.contains(&ident.as_str()) {
+            continue;
+        }
+
+        if elsewhere.contains(&ident.as_str()) {
+            return Err(syn::Error::new_spanned(
+                attr,
+                alloc::format!("#[{ident}] has no effect on {place}"),
+            ));
+        }
+
+        // short names like `doc` or `cfg` are too close to everything to guess at, and a short name with
+        // a letter swapped, like `node` for `code`, is as likely another derive's attribute as a typo
+        let close = |known: &&&str| match ident.len() {
+            0..=3 => false,
+            4..=5 => ident.len() != known.len() && levenshtein(&ident, known) == 1,
+            _ => levenshtein(&ident, known) <= 2,
+        };
+
+        let suggestion = ENUM_ATTRIBUTES
+            .iter()
+            .chain(VARIANT_ATTRIBUTES)
+            .filter(close)
+            .min_by_key(|known| levenshtein(&ident, known));
+
+        if let Some(known) = suggestion {
+            return Err(syn::Error::new_spanned(
+                attr,
+                alloc::format!("unknown attribute #[{ident}], did you mean #[{known}]?"),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// the number of single character edits between two strings
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut row = (0..=b.len()).collect::<Vec<usize>>();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, &b) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1)
+                .min(row[j] + 1)
+                .min(diagonal + usize::from(a != b));
+            diagonal = above;
+        }
+    }
+
+    row[b.len()]
+}
+
+/// parses the single `#[status(...)]` of the enum or a variant
+fn status_attr(attrs: &[Attribute], place: &str) -> syn::Result<Option<Expr>> {
+    let mut status_attrs = attrs.iter().filter(|attr| attr.path().is_ident("status"));
+
+    let Some(attr) = status_attrs.next() else {
+        return Ok(None);
+    };
+
+    // a second status would silently lose to the first
+    if let Some(duplicate) = status_attrs.next() {
+        return Err(syn::Error::new_spanned(
+            duplicate,
+            alloc::format!("only one #[status(...)] is allowed per {place}"),
+        ));
+    }
+
+    let Meta::List(list) = &attr.meta else {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "expected #[status(StatusCode::...)]",
+        ));
+    };
+
+    list.parse_args::<Expr>().map(Some)
+}
+
+/// parses a `#[name(path::to::fn)]` attribute
+fn path_attr(attrs: &[Attribute], name: &str) -> syn::Result<Option<Path>> {
+    attrs
+        .iter()
+        .find(|attr| attr.path().is_ident(name))
+        .map(|attr| attr.parse_args::<Path>())
+        .transpose()
+}
+
+/// finds a `#[name = "..."]` attribute and returns it with its value
+fn str_attr<'a>(attrs: &'a [Attribute], name: &str) -> Option<(&'a Attribute, String)> {
+    attrs
         .iter()
-        .find(|attr| attr.path().is_ident("internal_text"))
+        .find(|attr| attr.path().is_ident(name))
         .and_then(|attr| {
             if let Meta::NameValue(meta) = &attr.meta {
                 if let Expr::Lit(expr) = &meta.value {
                     if let Lit::Str(lit_str) = &expr.lit {
-                        return Some(lit_str.value());
+                        return Some((attr, lit_str.value()));
                     }
                 }
             }
             None
         })
-        .unwrap_or_else(|| String::from("Something went wrong"));
+}
 
-    // parse the attributes for status code override (if any)
-    let mut variant_overrides = Vec::new();
+/// the body a format string always formats to, or `None` when it has placeholders
+fn fixed_body(format: &str) -> Option<String> {
+    if format.replace("{{", "").contains('{') {
+        return None;
+    }
 
-    if let Data::Enum(data) = &input.data {
-        for variant in &data.variants {
-            let name = &variant.ident;
+    Some(unescape_braces(format))
+}
 
-            // make sure fields on the enum variants are matched
-            let fields = match &variant.fields {
-                Fields::Named(_) => quote! {{..}},
-                Fields::Unit => quote! {},
-                Fields::Unnamed(fields) => {
-                    let all_fields = fields
-                        .unnamed
-                        .iter()
-                        .map(|_| quote! {_})
-                        .collect::<Vec<proc_macro2::TokenStream>>();
+/// turns the `{{` and `}}` of a format string without placeholders into single braces
+fn unescape_braces(text: &str) -> String {
+    text.replace("{{", "{").replace("}}", "}")
+}
 
-                    quote! {
-                        (#(#all_fields),*)
+/// picks the example body of a variant from its `#[text = "..."]`, name, `#[error("...")]` format string
+/// or doc comment, leaving placeholders as they're written
+fn example_message(variant: &Variant, name_as_body: bool) -> String {
+    if let Some((_, text)) = str_attr(&variant.attrs, "text") {
+        return text;
+    }
+
+    if name_as_body {
+        return alloc::format!("{}", variant.ident);
+    }
+
+    let error = variant
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("error"))
+        .and_then(|attr| {
+            attr.parse_args_with(|input: syn::parse::ParseStream| {
+                let format = input.parse::<LitStr>()?;
+                input.parse::<proc_macro2::TokenStream>()?;
+                Ok(format.value())
+            })
+            .ok()
+        });
+
+    error
+        .or_else(|| {
+            str_attr(&variant.attrs, "doc")
+                .and_then(|(_, doc)| doc.lines().next().map(|line| String::from(line.trim())))
+        })
+        .unwrap_or_else(|| alloc::format!("{}", variant.ident))
+}
+
+/// binds the `#[source]` or `#[from]` field of a variant as `cause`, like thiserror picks it
+#[cfg(feature = "serde")]
+fn cause_pattern(variant: &Variant) -> Option<proc_macro2::TokenStream> {
+    let is_source = |field: &&syn::Field| {
+        field
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("source") || attr.path().is_ident("from"))
+            || field.ident.as_ref().is_some_and(|ident| ident == "source")
+    };
+
+    match &variant.fields {
+        Fields::Named(fields) => {
+            let field = &fields.named.iter().find(is_source)?.ident;
+            Some(quote! { { #field: cause, .. } })
+        }
+        Fields::Unnamed(fields) => {
+            let source = fields.unnamed.iter().position(|field| is_source(&field))?;
+            let pattern = (0..fields.unnamed.len()).map(|i| {
+                if i == source {
+                    quote! { cause }
+                } else {
+                    quote! { _ }
+                }
+            });
+            Some(quote! { (#(#pattern),*) })
+        }
+        Fields::Unit => None,
+    }
+}
+
+/// converts a `PascalCase` identifier to `SCREAMING_SNAKE_CASE`, keeping acronyms together
+#[cfg(feature = "serde")]
+fn screaming_snake_case(ident: &str) -> String {
+    let chars = ident.chars().collect::<Vec<char>>();
+    let mut out = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+
+            if prev != '_' && (!prev.is_uppercase() || next_lower) {
+                out.push('_');
+            }
+        }
+
+        out.extend(c.to_uppercase());
+    }
+
+    out
+}
+
+/// the naming of serialized keys picked with `#[json_case = "..."]`
+#[cfg(feature = "serde")]
+#[derive(Clone, Copy)]
+enum JsonCase {
+    Snake,
+    Camel,
+    Pascal,
+    Kebab,
+}
+
+#[cfg(feature = "serde")]
+impl JsonCase {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let Some((attr, case)) = str_attr(attrs, "json_case") else {
+            return Ok(Self::Snake);
+        };
+
+        match case.as_str() {
+            "snake_case" => Ok(Self::Snake),
+            "camelCase" => Ok(Self::Camel),
+            "PascalCase" => Ok(Self::Pascal),
+            "kebab-case" => Ok(Self::Kebab),
+            _ => Err(syn::Error::new_spanned(
+                attr,
+                "expected #[json_case = \"snake_case\" | \"camelCase\" | \"PascalCase\" | \"kebab-case\"]",
+            )),
+        }
+    }
+
+    /// converts a `snake_case` key
+    fn apply(self, key: &str) -> String {
+        match self {
+            Self::Snake => String::from(key),
+            Self::Kebab => key.replace('_', "-"),
+            Self::Camel | Self::Pascal => {
+                let mut out = String::new();
+
+                for (i, word) in key.split('_').enumerate() {
+                    let mut chars = word.chars();
+
+                    if let (Self::Camel, 0) = (self, i) {
+                        out.push_str(word);
+                    } else if let Some(first) = chars.next() {
+                        out.extend(first.to_uppercase());
+                        out.push_str(chars.as_str());
                     }
                 }
+
+                out
+            }
+        }
+    }
+}
+
+/// the fields serialized next to `status` and `error`
+#[cfg(feature = "serde")]
+struct Envelope {
+    /// constant `#[serde_field("key", "value")]` pairs
+    serde_fields: Vec<(LitStr, LitStr)>,
+    json_case: JsonCase,
+    /// `#[category = "..."]` match arms
+    categories: Vec<proc_macro2::TokenStream>,
+    /// leave out `kind` for variants without a category
+    skip_uncategorized: bool,
+    /// `error_code` match arms, one for every variant once any has a `#[code("...")]`
+    codes: Vec<proc_macro2::TokenStream>,
+    /// patterns of `#[delegate]` variants, serialized as the inner error
+    delegates: Vec<proc_macro2::TokenStream>,
+    /// patterns of `#[serde_inline_field]` variants, serializing the bound field as `error`
+    inline_fields: Vec<proc_macro2::TokenStream>,
+    /// `cause` match arms with `#[serde_cause]`, and whether to leave `cause` out instead of null
+    causes: Option<(Vec<proc_macro2::TokenStream>, bool)>,
+    internal_display: bool,
+    variant_count: usize,
+}
+
+#[cfg(feature = "serde")]
+fn serde_derive(
+    name: &proc_macro2::Ident,
+    generics: &syn::Generics,
+    envelope: &Envelope,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // every key goes through #[json_case = "..."]
+    let case = envelope.json_case;
+    let status_key = case.apply("status");
+    let error_key = case.apply("error");
+    let kind_key = case.apply("kind");
+    let code_key = case.apply("error_code");
+
+    // only add a kind when something is categorized
+    let (kind, kind_len, kind_field) = if envelope.categories.is_empty() {
+        (quote! {}, quote! { 0 }, quote! {})
+    } else {
+        let categories = &envelope.categories;
+        let uncategorized = fallback_arm(
+            categories.len(),
+            envelope.variant_count,
+            if envelope.skip_uncategorized {
+                quote! { None }
+            } else {
+                quote! { Some("internal") }
+            },
+        );
+
+        // a binary format has no keys to leave out, so a skipped kind is sent as None there to keep
+        // the layout of every variant the same
+        let (kind_len, kind_field) = if envelope.skip_uncategorized {
+            (
+                quote! { usize::from(!human_readable || kind.is_some()) },
+                quote! {
+                    match kind {
+                        Some(kind) if human_readable => _serde::ser::SerializeStruct::serialize_field(&mut __serde_state, #kind_key, kind)?,
+                        None if human_readable => _serde::ser::SerializeStruct::skip_field(&mut __serde_state, #kind_key)?,
+                        _ => _serde::ser::SerializeStruct::serialize_field(&mut __serde_state, #kind_key, &kind)?,
+                    }
+                },
+            )
+        } else {
+            (
+                quote! { 1 },
+                quote! {
+                    if let Some(kind) = kind {
+                        _serde::ser::SerializeStruct::serialize_field(&mut __serde_state, #kind_key, kind)?;
+                    }
+                },
+            )
+        };
+
+        (
+            quote! {
+                let kind: Option<&str> = match self {
+                    #(#categories)*
+                    #uncategorized
+                };
+            },
+            kind_len,
+            kind_field,
+        )
+    };
+
+    // a 500 hides its cause like its text, unless #[internal_display] shows both
+    let internal_display = envelope.internal_display;
+    let (cause, cause_len, cause_field) = match &envelope.causes {
+        None => (quote! {}, quote! { 0 }, quote! {}),
+        Some((causes, skip_causeless)) => {
+            let cause_fallback =
+                fallback_arm(causes.len(), envelope.variant_count, quote! { None });
+            let cause_key = case.apply("cause");
+
+            let cause = quote! {
+                let cause: Option<::std::string::String> = if mask && !#internal_display && status == 500 {
+                    None
+                } else {
+                    match self {
+                        #(#causes)*
+                        #cause_fallback
+                    }
+                };
             };
 
-            let attr = variant
-                .attrs
-                .iter()
-                .find(|attr| attr.path().is_ident("status"));
+            if *skip_causeless {
+                (
+                    cause,
+                    // like kind, a binary format gets the None to keep the layout of every variant
+                    quote! { usize::from(!human_readable || cause.is_some()) },
+                    quote! {
+                        match &cause {
+                            None if human_readable => _serde::ser::SerializeStruct::skip_field(&mut __serde_state, #cause_key)?,
+                            _ => _serde::ser::SerializeStruct::serialize_field(&mut __serde_state, #cause_key, &cause)?,
+                        }
+                    },
+                )
+            } else {
+                (
+                    cause,
+                    quote! { 1 },
+                    quote! {
+                        _serde::ser::SerializeStruct::serialize_field(&mut __serde_state, #cause_key, &cause)?;
+                    },
+                )
+            }
+        }
+    };
 
-            if let Some(attr) = attr {
-                // extract the status and build the tokens
-                if let Meta::List(list) = &attr.meta {
-                    let status = &list.tokens;
+    let skips = (envelope.skip_uncategorized && !envelope.categories.is_empty())
+        || matches!(envelope.causes, Some((_, true)));
+    let human_readable = if skips {
+        quote! { let human_readable = _serde::Serializer::is_human_readable(&__serializer); }
+    } else {
+        quote! {}
+    };
 
-                    let status = quote! {
-                        Self::#name #fields => ::axum::http::status::#status,
-                    };
+    let (code, code_len, code_field) = if envelope.codes.is_empty() {
+        (quote! {}, 0_usize, quote! {})
+    } else {
+        let codes = &envelope.codes;
+        let code_fallback = fallback_arm(codes.len(), envelope.variant_count, quote! { "" });
 
-                    variant_overrides.push(status);
-                }
+        (
+            quote! {
+                let code: &str = match self {
+                    #(#codes)*
+                    #code_fallback
+                };
+            },
+            1,
+            quote! {
+                _serde::ser::SerializeStruct::serialize_field(&mut __serde_state, #code_key, code)?;
+            },
+        )
+    };
+
+    let delegate = if envelope.delegates.is_empty() {
+        quote! {}
+    } else {
+        let delegates = &envelope.delegates;
+        quote! {
+            match self {
+                #(#delegates => return _serde::Serialize::serialize(inner, __serializer),)*
+                _ => {}
             }
         }
+    };
+
+    // a 500 still hides the inlined field
+    let error_field = if envelope.inline_fields.is_empty() {
+        quote! {
+            _serde::ser::SerializeStruct::serialize_field(&mut __serde_state, #error_key, &text)?;
+        }
     } else {
-        panic!("IntoResponse can only be derived on an Enum");
-    }
+        let inline_fields = &envelope.inline_fields;
+        quote! {
+            match self {
+                #(#inline_fields if !mask || status != 500 => _serde::ser::SerializeStruct::serialize_field(&mut __serde_state, #error_key, inner)?,)*
+                _ => _serde::ser::SerializeStruct::serialize_field(&mut __serde_state, #error_key, &text)?,
+            }
+        }
+    };
 
-    let tracing = {
+    let extra_len = envelope.serde_fields.len();
+    let extra_fields = envelope.serde_fields.iter().map(|(key, value)| {
+        let key = case.apply(&key.value());
+        quote! {
+            _serde::ser::SerializeStruct::serialize_field(&mut __serde_state, #key, #value)?;
+        }
+    });
+
+    let json_value = {
         #[allow(unused)]
         let mut stream = proc_macro2::TokenStream::new();
-        #[cfg(feature = "tracing")]
+        #[cfg(feature = "serde_json")]
         {
-            let err = quote! {
-                let internal_err = self.to_string();
-                ::tracing::error!("{internal_err}");
+            let value = quote! {
+                #[automatically_derived]
+                impl #impl_generics #name #ty_generics #where_clause {
+                    /// Returns the serialized error as a `serde_json::Value`
+                    #[must_use]
+                    pub fn to_json_value(&self) -> ::serde_json::Value {
+                        ::serde_json::to_value(self).unwrap_or(::serde_json::Value::Null)
+                    }
+                }
             };
 
-            stream = err;
+            stream = value;
         }
         stream
     };
 
-    // build the impl
-    #[allow(unused_mut)]
-    let mut expanded = quote! {
-        #[automatically_derived]
-        impl ::axum::response::IntoResponse for #name {
-            fn into_response(self) -> ::axum::response::Response {
-                let status = match self {
-                    #(#variant_overrides)*
-                    _ => ::axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                };
+    quote! {
+        #json_value
 
-                let text = {
-                    if status == ::axum::http::StatusCode::INTERNAL_SERVER_ERROR {
-                        #tracing
-                        #internal_text.to_string()
-                    } else {
-                        self.to_string()
-                    }
-                };
+        // scoped so several derives can share a module
+        const _: () = {
+            extern crate serde as _serde;
+            // the masking of a json body follows the response instead of always hiding a 500
+            trait __IntoResponseSerializeMasked {
+                fn serialize_masked<__S>(&self, __serializer: __S, mask: bool) -> Result<__S::Ok, __S::Error>
+                where
+                    __S: _serde::Serializer;
+            }
 
-                ::axum::response::IntoResponse::into_response((status, text))
+            struct __IntoResponseMasked<'m, T>(&'m T, bool);
+
+            impl<T: __IntoResponseSerializeMasked> _serde::Serialize for __IntoResponseMasked<'_, T> {
+                fn serialize<__S>(&self, __serializer: __S) -> Result<__S::Ok, __S::Error>
+                where
+                    __S: _serde::Serializer,
+                {
+                    self.0.serialize_masked(__serializer, self.1)
+                }
             }
-        }
-    };
 
-    #[cfg(feature = "serde")]
-    {
-        let ser = serde_derive(&name, &variant_overrides, &internal_text);
-        expanded.extend([ser]);
-    }
+            #[automatically_derived]
+            impl #impl_generics #name #ty_generics #where_clause {
+                #[allow(dead_code)]
+                fn __into_response_json(&self, status: ::axum::http::StatusCode, mask: bool) -> ::axum::response::Response {
+                    ::axum::response::IntoResponse::into_response((status, ::axum::Json(__IntoResponseMasked(self, mask))))
+                }
+            }
 
-    expanded.into()
+            #[automatically_derived]
+            impl #impl_generics _serde::Serialize for #name #ty_generics #where_clause {
+                fn serialize<__S>(&self, __serializer: __S) -> Result<__S::Ok, __S::Error>
+                where
+                    __S: _serde::Serializer,
+                {
+                    __IntoResponseSerializeMasked::serialize_masked(self, __serializer, true)
+                }
+            }
+
+            #[automatically_derived]
+            impl #impl_generics __IntoResponseSerializeMasked for #name #ty_generics #where_clause {
+                fn serialize_masked<__S>(&self, __serializer: __S, mask: bool) -> Result<__S::Ok, __S::Error>
+                where
+                    __S: _serde::Serializer,
+                {
+                    #delegate
+
+                    #human_readable
+                    let status = self.status_code().as_u16();
+                    let text = self.response_text(mask);
+                    #kind
+                    #code
+                    #cause
+
+                    let mut __serde_state = _serde::Serializer::serialize_struct(__serializer, "", false as usize + 1 + 1 + #kind_len + #code_len + #cause_len + #extra_len)?;
+                    _serde::ser::SerializeStruct::serialize_field(&mut __serde_state, #status_key, &status)?;
+                    #error_field
+                    #kind_field
+                    #code_field
+                    #cause_field
+                    #(#extra_fields)*
+                    _serde::ser::SerializeStruct::end(__serde_state)
+                }
+            }
+        };
+    }
 }
 
-#[cfg(feature = "serde")]
-fn serde_derive(
+#[cfg(feature = "bincode")]
+fn bincode_derive(
     name: &proc_macro2::Ident,
-    variant_overrides: &Vec<proc_macro2::TokenStream>,
-    internal_text: &str,
+    generics: &syn::Generics,
+    tracing: &proc_macro2::TokenStream,
 ) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
     quote! {
-        extern crate serde as _serde;
         #[automatically_derived]
-        impl _serde::Serialize for #name {
-            fn serialize<__S>(&self, __serializer: __S) -> Result<__S::Ok, __S::Error>
-            where
-                __S: _serde::Serializer,
-            {
-                let status = match self {
-                    #(#variant_overrides)*
-                    _ => ::axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                }.as_u16();
-
-                let text = {
-                    if status == ::axum::http::StatusCode::INTERNAL_SERVER_ERROR {
-                        #internal_text.to_string()
-                    } else {
-                        self.to_string()
-                    }
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Builds a response with the serialized error encoded with bincode as the body
+            #[must_use]
+            pub fn into_bincode_response(self) -> ::axum::response::Response {
+                let status = self.status_code();
+
+                if status == ::axum::http::StatusCode::INTERNAL_SERVER_ERROR {
+                    #tracing
+                }
+
+                let mut response = match ::bincode::serde::encode_to_vec(&self, ::bincode::config::standard()) {
+                    Ok(body) => ::axum::response::IntoResponse::into_response((
+                        status,
+                        [(::axum::http::header::CONTENT_TYPE, "application/octet-stream")],
+                        body,
+                    )),
+                    Err(_) => ::axum::response::IntoResponse::into_response(
+                        ::axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    ),
                 };
 
-                let mut __serde_state = _serde::Serializer::serialize_struct(__serializer, "", false as usize + 1 + 1)?;
-                _serde::ser::SerializeStruct::serialize_field(&mut __serde_state, "status", &status)?;
-                _serde::ser::SerializeStruct::serialize_field(&mut __serde_state, "error", &text)?;
-                _serde::ser::SerializeStruct::end(__serde_state)
+                self.__into_response_finish(&mut response);
+                response
             }
         }
     }
@@ -0,0 +1,8 @@
+//! A derive with short helper attributes, to check they aren't taken for misspelled ones
+
+use proc_macro::TokenStream;
+
+#[proc_macro_derive(Other, attributes(node, mode, core, body, case, hide, next))]
+pub fn derive_other(_: TokenStream) -> TokenStream {
+    TokenStream::new()
+}
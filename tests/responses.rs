@@ -0,0 +1,267 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use axum_thiserror_tracing::IntoResponse;
+use thiserror::Error;
+use tower::ServiceExt;
+
+#[derive(Debug, Error, IntoResponse)]
+#[internal_text = "overridden"]
+#[serde_field("service", "example")]
+pub enum AppError {
+    #[error("This shouldn't show in the response, but will in tracing")]
+    Internal,
+    #[status(StatusCode::BAD_REQUEST)]
+    #[error("Bad request")]
+    ClientError,
+    #[status(StatusCode::UNAUTHORIZED)]
+    #[error("Error: {0}")]
+    AuthError(&'static str),
+}
+
+fn app() -> Router {
+    let router = Router::new()
+        .route("/internal", get(|| async { AppError::Internal }))
+        .route("/client", get(|| async { AppError::ClientError }))
+        .route("/auth", get(|| async { AppError::AuthError("x") }));
+
+    #[cfg(feature = "serde")]
+    let router = router.route("/json", get(|| async { axum::Json(AppError::ClientError) }));
+
+    router
+}
+
+/// sends a request through the router and collects the response
+async fn send(uri: &str) -> (StatusCode, String) {
+    let res = app()
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .into_response();
+
+    let status = res.status();
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn internal_is_masked() {
+    let (status, body) = send("/internal").await;
+
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body, "overridden");
+}
+
+#[tokio::test]
+async fn status_uses_the_error_text() {
+    let (status, body) = send("/client").await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body, "Bad request");
+}
+
+#[tokio::test]
+async fn fields_are_formatted() {
+    let (status, body) = send("/auth").await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body, "Error: x");
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test]
+async fn json_envelope() {
+    let (status, body) = send("/json").await;
+
+    // Json(...) responds with 200, the status is in the envelope
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+        serde_json::json!({ "status": 400, "error": "Bad request", "service": "example" })
+    );
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn causes_can_be_left_out() {
+    #[derive(Debug, Error, IntoResponse)]
+    #[serde_cause(skip_none)]
+    pub enum UploadError {
+        #[status(StatusCode::BAD_REQUEST)]
+        #[error("Invalid manifest")]
+        Manifest {
+            #[source]
+            source: std::fmt::Error,
+        },
+        #[status(StatusCode::PAYLOAD_TOO_LARGE)]
+        #[error("File too large")]
+        TooLarge,
+        #[error("Storage is down")]
+        Storage(#[source] std::fmt::Error),
+    }
+
+    let value = UploadError::Manifest {
+        source: std::fmt::Error,
+    }
+    .to_json_value();
+    assert_eq!(
+        value["cause"],
+        "an error occurred when formatting an argument"
+    );
+
+    let value = UploadError::TooLarge.to_json_value();
+    assert!(value.get("cause").is_none());
+
+    // the cause of a 500 is hidden like its text
+    let value = UploadError::Storage(std::fmt::Error).to_json_value();
+    assert!(value.get("cause").is_none());
+}
+
+#[tokio::test]
+async fn body_bytes_are_masked_on_500() {
+    #[derive(Debug, Error, IntoResponse)]
+    pub enum RpcError {
+        #[body_bytes(encode)]
+        #[error("Connection refused")]
+        Unavailable,
+    }
+
+    fn encode(err: &RpcError) -> Vec<u8> {
+        err.to_string().into_bytes()
+    }
+
+    let res = RpcError::Unavailable.into_response();
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(res.headers()["content-type"], "text/plain; charset=utf-8");
+
+    let res = RpcError::Unavailable.into_response_masked(false);
+    assert_eq!(res.headers()["content-type"], "application/octet-stream");
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], b"Connection refused");
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test]
+async fn json_bodies_follow_the_mask() {
+    #[derive(Debug, Error, IntoResponse)]
+    #[response(json)]
+    pub enum ApiError {
+        #[error("Database connection refused")]
+        Database,
+    }
+
+    for (mask, error) in [
+        (true, "Something went wrong"),
+        (false, "Database connection refused"),
+    ] {
+        let res = ApiError::Database.into_response_masked(mask);
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            serde_json::json!({ "status": 500, "error": error })
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test]
+async fn helper_names_dont_clash() {
+    // the json helpers live next to the derived impls, so they mustn't shadow the enum
+    #[derive(Debug, Error, IntoResponse)]
+    #[response(json)]
+    pub enum Masked {
+        #[status(StatusCode::BAD_REQUEST)]
+        #[error("Bad request")]
+        BadRequest,
+    }
+
+    let res = Masked::BadRequest.into_response();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+        serde_json::json!({ "status": 400, "error": "Bad request" })
+    );
+}
+
+#[test]
+fn own_methods_dont_clash() {
+    // the helpers behind the accessors mustn't take names the enum may already use
+    #[derive(Debug, Error, IntoResponse)]
+    #[cfg_attr(feature = "serde", response(json))]
+    pub enum JobError {
+        #[status(StatusCode::CONFLICT)]
+        #[error("Job is running")]
+        Running,
+    }
+
+    #[allow(dead_code)]
+    impl JobError {
+        fn body_response(&self) {}
+        fn finish_response(&self) {}
+        fn traced_error(&self) {}
+        fn json_response(&self) {}
+    }
+
+    assert_eq!(JobError::Running.status_code(), StatusCode::CONFLICT);
+}
+
+#[cfg(feature = "bincode")]
+#[tokio::test]
+async fn bincode_layout_is_fixed() {
+    #[derive(Debug, Error, IntoResponse)]
+    #[skip_uncategorized]
+    #[serde_cause(skip_none)]
+    pub enum AppError {
+        #[status(StatusCode::BAD_REQUEST)]
+        #[category = "validation"]
+        #[error("Invalid manifest")]
+        Manifest(#[source] std::fmt::Error),
+        #[status(StatusCode::NOT_FOUND)]
+        #[error("No such user")]
+        NotFound,
+    }
+
+    async fn decode(err: AppError) -> (u16, String, Option<String>, Option<String>) {
+        let res = err.into_bincode_response();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        bincode::serde::decode_from_slice(&body, bincode::config::standard())
+            .unwrap()
+            .0
+    }
+
+    // a binary format can't leave fields out, so they're sent as None
+    assert_eq!(
+        decode(AppError::Manifest(std::fmt::Error)).await,
+        (
+            400,
+            String::from("Invalid manifest"),
+            Some(String::from("validation")),
+            Some(String::from(
+                "an error occurred when formatting an argument"
+            )),
+        )
+    );
+    assert_eq!(
+        decode(AppError::NotFound).await,
+        (404, String::from("No such user"), None, None)
+    );
+}
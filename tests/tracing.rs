@@ -0,0 +1,98 @@
+#![cfg(feature = "tracing")]
+
+use axum::{http::StatusCode, response::IntoResponse};
+use axum_thiserror_tracing::IntoResponse;
+use std::{
+    error::Error as _,
+    fmt,
+    sync::{Arc, Mutex},
+};
+use thiserror::Error;
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+/// collects the fields of every event
+#[derive(Clone, Default)]
+struct Collector {
+    fields: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+impl Visit for Collector {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.fields
+            .lock()
+            .unwrap()
+            .push((field.name().to_string(), format!("{value:?}")));
+    }
+}
+
+impl Subscriber for Collector {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        event.record(&mut self.clone());
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+impl Collector {
+    fn field(&self, name: &str) -> Option<String> {
+        self.fields
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.clone())
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("connection reset")]
+struct Io;
+
+#[derive(Debug, Error)]
+#[error("query failed")]
+struct Query(#[source] Io);
+
+#[derive(Debug, Error, IntoResponse)]
+#[internal_text = "overridden"]
+pub enum AppError {
+    #[error("{0}")]
+    Other(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+#[tokio::test]
+async fn boxed_errors_log_their_source() {
+    let collector = Collector::default();
+    let err = AppError::from(Box::new(Query(Io)) as Box<_>);
+    assert!(err.source().is_some());
+
+    let res = tracing::subscriber::with_default(collector.clone(), || err.into_response());
+
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], b"overridden");
+
+    assert_eq!(
+        collector.field("error").as_deref(),
+        Some("query failed: connection reset")
+    );
+    assert_eq!(collector.field("variant").as_deref(), Some("\"Other\""));
+}
@@ -0,0 +1,25 @@
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/empty_enum.rs");
+    t.pass("tests/ui/enum_error_fmt.rs");
+    t.pass("tests/ui/short_attributes.rs");
+    t.compile_fail("tests/ui/not_an_enum.rs");
+    t.compile_fail("tests/ui/status_table_overflow.rs");
+
+    // attributes that conflict or are misplaced
+    t.compile_fail("tests/ui/duplicate_status.rs");
+    t.compile_fail("tests/ui/duplicate_body.rs");
+    t.compile_fail("tests/ui/misplaced_attribute.rs");
+    t.compile_fail("tests/ui/misspelled_attribute.rs");
+    t.compile_fail("tests/ui/unknown_text_field.rs");
+    t.compile_fail("tests/ui/internal_html_with_text.rs");
+    t.compile_fail("tests/ui/internal_html_with_display.rs");
+    t.compile_fail("tests/ui/internal_text_fn_with_html.rs");
+    t.compile_fail("tests/ui/serde_inline_field_pair.rs");
+    t.compile_fail("tests/ui/delegate_attribute.rs");
+    t.compile_fail("tests/ui/status_fn_with_status.rs");
+    t.compile_fail("tests/ui/reason_phrase_body_with_message_fn.rs");
+
+    t.compile_fail("tests/ui/unused_accessor.rs");
+}
@@ -0,0 +1,19 @@
+use axum_thiserror_tracing::IntoResponse;
+use thiserror::Error;
+
+#[derive(Debug, Error, IntoResponse)]
+pub enum DbError {
+    #[status(StatusCode::NOT_FOUND)]
+    #[error("No such row")]
+    NotFound,
+}
+
+#[derive(Debug, Error, IntoResponse)]
+pub enum AppError {
+    #[delegate]
+    #[text = "ignored?"]
+    #[error(transparent)]
+    Db(DbError),
+}
+
+fn main() {}
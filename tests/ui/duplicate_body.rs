@@ -0,0 +1,14 @@
+use axum_thiserror_tracing::IntoResponse;
+use thiserror::Error;
+
+#[derive(Debug, Error, IntoResponse)]
+pub enum AppError {
+    #[status(StatusCode::BAD_REQUEST)]
+    #[error("Missing name")]
+    MissingName,
+    #[status(StatusCode::BAD_REQUEST)]
+    #[error("Missing name")]
+    MissingEmail,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use axum_thiserror_tracing::IntoResponse;
+use thiserror::Error;
+
+#[derive(Debug, Error, IntoResponse)]
+pub enum AppError {
+    #[status(StatusCode::BAD_REQUEST)]
+    #[status(StatusCode::NOT_FOUND)]
+    #[error("Which one?")]
+    Ambiguous,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+#![deny(warnings)]
+
+use axum_thiserror_tracing::IntoResponse;
+use thiserror::Error;
+
+// uninhabited, e.g. for a handler that can't fail yet
+#[derive(Debug, Error, IntoResponse)]
+#[internal_text = "overridden"]
+pub enum AppError {}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use axum_thiserror_tracing::IntoResponse;
+use std::fmt;
+use thiserror::Error;
+
+// the enum's #[error(fmt = ...)] displays every variant, so they need no #[error] of their own
+#[derive(Debug, Error, IntoResponse)]
+#[error(fmt = display)]
+pub enum AppError {
+    #[status(StatusCode::BAD_REQUEST)]
+    BadRequest(&'static str),
+    Internal(&'static str),
+}
+
+fn display(message: &&'static str, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(message)
+}
+
+fn main() {
+    assert_eq!(AppError::BadRequest("Bad request").response_text(true), "Bad request");
+    assert_eq!(AppError::Internal("Internal").status_code(), 500);
+}
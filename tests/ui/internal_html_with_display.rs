@@ -0,0 +1,12 @@
+use axum_thiserror_tracing::IntoResponse;
+use thiserror::Error;
+
+#[derive(Debug, Error, IntoResponse)]
+#[internal_display]
+#[internal_html = "<h1>Oops</h1>"]
+pub enum JobError {
+    #[error("<script>alert(1)</script>")]
+    Injected,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use axum_thiserror_tracing::IntoResponse;
+use thiserror::Error;
+
+#[derive(Debug, Error, IntoResponse)]
+#[internal_text = "Something went wrong"]
+#[internal_html = "<h1>Oops</h1>"]
+pub enum AppError {
+    #[error("Database connection refused")]
+    Database,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use axum_thiserror_tracing::IntoResponse;
+use thiserror::Error;
+
+#[derive(Debug, Error, IntoResponse)]
+#[internal_html = "<h1>Oops</h1>"]
+#[internal_text_fn(internal_text)]
+pub enum AppError {
+    #[error("Database connection refused")]
+    Database,
+}
+
+fn internal_text() -> String {
+    String::from("<script>alert(1)</script>")
+}
+
+fn main() {}
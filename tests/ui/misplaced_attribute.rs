@@ -0,0 +1,12 @@
+use axum_thiserror_tracing::IntoResponse;
+use thiserror::Error;
+
+#[derive(Debug, Error, IntoResponse)]
+pub enum AppError {
+    // only read from the enum
+    #[no_cache]
+    #[error("Stale")]
+    Stale,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use axum_thiserror_tracing::IntoResponse;
+use thiserror::Error;
+
+#[derive(Debug, Error, IntoResponse)]
+pub enum AppError {
+    #[staus(StatusCode::BAD_REQUEST)]
+    #[error("Bad request")]
+    BadRequest,
+}
+
+fn main() {}
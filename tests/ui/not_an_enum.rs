@@ -0,0 +1,8 @@
+use axum_thiserror_tracing::IntoResponse;
+use thiserror::Error;
+
+#[derive(Debug, Error, IntoResponse)]
+#[error("Something broke")]
+pub struct AppError;
+
+fn main() {}
@@ -0,0 +1,17 @@
+use axum_thiserror_tracing::IntoResponse;
+use thiserror::Error;
+
+#[derive(Debug, Error, IntoResponse)]
+#[reason_phrase_body]
+#[message_fn(message)]
+pub enum AppError {
+    #[status(StatusCode::BAD_REQUEST)]
+    #[error("Bad request")]
+    BadRequest,
+}
+
+fn message(_: &AppError) -> String {
+    String::new()
+}
+
+fn main() {}
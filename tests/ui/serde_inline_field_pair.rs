@@ -0,0 +1,12 @@
+use axum_thiserror_tracing::IntoResponse;
+use thiserror::Error;
+
+#[derive(Debug, Error, IntoResponse)]
+pub enum AppError {
+    // only a single field can be inlined
+    #[serde_inline_field]
+    #[error("{0} {1}")]
+    Pair(u8, u8),
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use axum_thiserror_tracing::IntoResponse;
+use other_derive::Other;
+use thiserror::Error;
+
+// short helper attributes of other derives are one letter off `code` and `text`, not typos of them
+#[derive(Debug, Error, IntoResponse, Other)]
+pub enum AppError {
+    #[node]
+    #[mode]
+    #[core]
+    #[body]
+    #[case]
+    #[hide]
+    #[next]
+    #[error("Something broke")]
+    Internal,
+}
+
+fn main() {}
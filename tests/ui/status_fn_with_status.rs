@@ -0,0 +1,16 @@
+use axum_thiserror_tracing::IntoResponse;
+use thiserror::Error;
+
+#[derive(Debug, Error, IntoResponse)]
+#[status(StatusCode::BAD_REQUEST)]
+#[status_fn(status)]
+pub enum AppError {
+    #[error("Bad request")]
+    BadRequest,
+}
+
+fn status(_: &AppError) -> axum::http::StatusCode {
+    axum::http::StatusCode::BAD_REQUEST
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use axum_thiserror_tracing::IntoResponse;
+use thiserror::Error;
+
+#[derive(Clone, Copy, Debug, Error, IntoResponse)]
+#[status_table]
+#[repr(u32)]
+pub enum AppError {
+    #[status(StatusCode::BAD_REQUEST)]
+    #[error("Bad request")]
+    BadRequest = 1,
+    #[status(StatusCode::NOT_FOUND)]
+    #[error("Not found")]
+    NotFound = 65537,
+}
+
+fn main() {
+    let _ = AppError::NotFound.status_code();
+}
@@ -0,0 +1,12 @@
+use axum_thiserror_tracing::IntoResponse;
+use thiserror::Error;
+
+#[derive(Debug, Error, IntoResponse)]
+pub enum AppError {
+    #[status(StatusCode::NOT_FOUND)]
+    #[text = "User {name} not found"]
+    #[error("lookup of user {id} failed")]
+    UserNotFound { id: u64 },
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+#![deny(unused_must_use)]
+use axum_thiserror_tracing::IntoResponse;
+use thiserror::Error;
+
+#[derive(Debug, Error, IntoResponse)]
+pub enum AppError {
+    #[error("Something broke")]
+    Internal,
+}
+
+fn main() {
+    AppError::Internal.status_code();
+}